@@ -0,0 +1,34 @@
+use crate::encode::utils::lsb::{set_lsbs_at, LsbSample};
+use crate::error::Error;
+use crate::traits::Carrier;
+use crate::utils::audio::{load_carrier_audio, write_pcm16, PcmAudio};
+use std::path::Path;
+
+/// One bit per interleaved sample. Any format ffmpeg reads can be loaded,
+/// but only WAV, AIFF, AIFF-C and CAF can be saved.
+impl Carrier for PcmAudio {
+    fn load(path: &str) -> Result<Self, Error> {
+        load_carrier_audio(path)
+    }
+
+    fn save(&self, path: &str) -> Result<(), Error> {
+        write_pcm16(Path::new(path), self)
+    }
+
+    fn capacity(&self) -> usize {
+        self.samples.len()
+    }
+
+    fn read_bits(&self) -> Vec<u8> {
+        self.samples.iter().map(|sample| sample.lsb()).collect()
+    }
+
+    fn write_bits(&mut self, start: usize, bits: &[u8]) {
+        set_lsbs_at(&mut self.samples, start, bits);
+    }
+
+    fn step(&mut self, index: usize, up: bool) {
+        let value = &mut self.samples[index];
+        *value = value.step(up);
+    }
+}
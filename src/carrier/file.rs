@@ -0,0 +1,98 @@
+use crate::encode::utils::lsb::{set_lsbs_at, LsbSample};
+use crate::error::Error;
+use crate::traits::{Carrier, ContentType};
+use crate::utils::audio::AudioFormat;
+use crate::utils::jpeg::is_jpeg;
+use crate::utils::png::is_png;
+use std::fs;
+use std::path::Path;
+
+/// A carrier file kept as it is on disk, for methods that hide data in the
+/// file's structure rather than in its decoded pixels or samples. Its values
+/// are the file's bytes.
+pub struct CarrierFile {
+    pub content_type: ContentType,
+    pub bytes: Vec<u8>,
+}
+
+impl CarrierFile {
+    pub fn read(path: &str, content_type: ContentType) -> Result<Self, Error> {
+        let bytes = fs::read(path).map_err(|e| {
+            Error::io(
+                format!(
+                    "Failed to read carrier {} '{}'",
+                    content_type.to_string(),
+                    path
+                ),
+                e,
+            )
+        })?;
+        Ok(CarrierFile {
+            content_type,
+            bytes,
+        })
+    }
+
+    /// Rejects an output path whose extension names a different format than
+    /// the file, which would keep the data but mislabel the file.
+    fn check_output_path(&self, path: &str) -> Result<(), Error> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_lowercase());
+
+        if is_png(&self.bytes) && extension.as_deref() != Some("png") {
+            return Err(Error::InvalidInput(format!(
+                "Output image path '{}' must have a .png extension to match the PNG carrier.",
+                path
+            )));
+        }
+        if is_jpeg(&self.bytes) && !matches!(extension.as_deref(), Some("jpg") | Some("jpeg")) {
+            return Err(Error::InvalidInput(format!(
+                "Output image path '{}' must have a .jpg or .jpeg extension to match the JPEG carrier.",
+                path
+            )));
+        }
+        match AudioFormat::from_bytes(&self.bytes) {
+            Some(format) if AudioFormat::from_path(Path::new(path)) != Some(format) => {
+                Err(Error::InvalidInput(format!(
+                    "Output audio path '{}' must use the same container as the carrier ({:?}).",
+                    path, format
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The content type is taken from the file's extension.
+impl Carrier for CarrierFile {
+    fn load(path: &str) -> Result<Self, Error> {
+        let content_type = ContentType::from_path(path).ok_or_else(|| {
+            Error::InvalidInput(format!("Could not determine carrier type for '{}'", path))
+        })?;
+        Self::read(path, content_type)
+    }
+
+    fn save(&self, path: &str) -> Result<(), Error> {
+        self.check_output_path(path)?;
+        fs::write(path, &self.bytes)
+            .map_err(|e| Error::io(format!("Failed to write output file '{}'", path), e))
+    }
+
+    fn capacity(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn read_bits(&self) -> Vec<u8> {
+        self.bytes.iter().map(|byte| byte.lsb()).collect()
+    }
+
+    fn write_bits(&mut self, start: usize, bits: &[u8]) {
+        set_lsbs_at(&mut self.bytes, start, bits);
+    }
+
+    fn step(&mut self, index: usize, up: bool) {
+        self.bytes[index] = self.bytes[index].step(up);
+    }
+}
@@ -0,0 +1,37 @@
+use crate::encode::utils::lsb::{set_lsbs_at, LsbSample};
+use crate::error::Error;
+use crate::traits::Carrier;
+use crate::utils::load::{load_image, save_image};
+use image::RgbImage;
+
+/// One bit per RGB channel value, in row-major order.
+impl Carrier for RgbImage {
+    fn load(path: &str) -> Result<Self, Error> {
+        Ok(load_image(path)?.to_rgb8())
+    }
+
+    fn save(&self, path: &str) -> Result<(), Error> {
+        save_image(self, path)
+    }
+
+    fn capacity(&self) -> usize {
+        self.as_flat_samples().samples.len()
+    }
+
+    fn read_bits(&self) -> Vec<u8> {
+        self.as_flat_samples()
+            .samples
+            .iter()
+            .map(|value| value.lsb())
+            .collect()
+    }
+
+    fn write_bits(&mut self, start: usize, bits: &[u8]) {
+        set_lsbs_at(self.as_flat_samples_mut().samples, start, bits);
+    }
+
+    fn step(&mut self, index: usize, up: bool) {
+        let value = &mut self.as_flat_samples_mut().samples[index];
+        *value = value.step(up);
+    }
+}
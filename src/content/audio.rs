@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::traits::{ContentType, Decodable, Encodable};
 use std::fs;
 use std::path::Path;
@@ -14,21 +15,24 @@ pub struct AudioContent {
 }
 
 impl AudioContent {
-    pub fn new(path: &str) -> Result<Self, String> {
+    pub fn new(path: &str) -> Result<Self, Error> {
         let input_path = Path::new(path);
         if !input_path.exists() {
-            return Err(format!("Input audio file not found: {}", path));
+            return Err(Error::InvalidInput(format!(
+                "Input audio file not found: {}",
+                path
+            )));
         }
 
         let temp_output_file =
-            NamedTempFile::new().map_err(|e| format!("Failed to create temp file: {}", e))?;
+            NamedTempFile::new().map_err(|e| Error::io("Failed to create temp file", e))?;
 
         let temp_output_path_with_ext = temp_output_file
             .path()
             .with_extension(TARGET_AUDIO_EXTENSION);
         let temp_output_path_str = temp_output_path_with_ext
             .to_str()
-            .ok_or("Invalid temporary file path string")?;
+            .ok_or_else(|| Error::codec("Invalid temporary file path string", None))?;
 
         println!(
             "Compressing audio '{}' to {} at {} using ffmpeg...",
@@ -46,23 +50,26 @@ impl AudioContent {
             .arg(temp_output_path_str)
             .output()
             .map_err(|e| {
-                format!(
-                    "Failed to execute ffmpeg. Is it installed and in PATH? Error: {}",
-                    e
+                Error::codec(
+                    "Failed to execute ffmpeg. Is it installed and in PATH?",
+                    Some(e),
                 )
             })?;
 
         if !ffmpeg_output.status.success() {
             let stderr = String::from_utf8_lossy(&ffmpeg_output.stderr);
-            return Err(format!(
-                "ffmpeg failed to compress audio (exit code: {:?}). Stderr:\n{}",
-                ffmpeg_output.status.code(),
-                stderr
+            return Err(Error::codec(
+                format!(
+                    "ffmpeg failed to compress audio (exit code: {:?}). Stderr:\n{}",
+                    ffmpeg_output.status.code(),
+                    stderr
+                ),
+                None,
             ));
         }
 
         let compressed_audio_bytes = fs::read(temp_output_path_str)
-            .map_err(|e| format!("Failed to read compressed audio from temp file: {}", e))?;
+            .map_err(|e| Error::io("Failed to read compressed audio from temp file", e))?;
 
         Ok(Self {
             compressed_audio_bytes,
@@ -98,10 +105,12 @@ impl Encodable for AudioContent {
 }
 
 impl Decodable for AudioContent {
-    fn from_bytes(data: &[u8]) -> Result<Self, String> {
+    fn from_bytes(data: &[u8]) -> Result<Self, Error> {
         if data.len() < 4 {
             // Minimum for extension length
-            return Err("Not enough data for audio content (extension length)".to_string());
+            return Err(Error::Corrupt(
+                "Not enough data for audio content (extension length)".to_string(),
+            ));
         }
 
         let mut ext_len_bytes = [0u8; 4];
@@ -110,14 +119,16 @@ impl Decodable for AudioContent {
 
         let header_end_offset = 4 + ext_len;
         if data.len() < header_end_offset {
-            return Err(format!(
+            return Err(Error::Corrupt(format!(
                 "Not enough data for audio content (extension string). Expected {} bytes for ext, data has {}.",
                 ext_len, data.len() - 4
-            ));
+            )));
         }
 
-        let target_extension = String::from_utf8(data[4..header_end_offset].to_vec())
-            .map_err(|e| format!("Failed to decode audio target extension: {}", e))?;
+        let target_extension =
+            String::from_utf8(data[4..header_end_offset].to_vec()).map_err(|e| {
+                Error::Corrupt(format!("Failed to decode audio target extension: {}", e))
+            })?;
 
         let compressed_audio_bytes = data[header_end_offset..].to_vec();
 
@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::traits::{ContentType, Decodable, Encodable};
 use crate::utils::load::load_image_and_resize;
 use image::{DynamicImage, RgbImage};
@@ -7,10 +8,10 @@ pub struct ImageContent {
 }
 
 impl ImageContent {
-    pub fn new(path: &str) -> Self {
-        Self {
-            image: load_image_and_resize(path),
-        }
+    pub fn new(path: &str) -> Result<Self, Error> {
+        Ok(Self {
+            image: load_image_and_resize(path)?,
+        })
     }
 }
 
@@ -37,9 +38,11 @@ impl Encodable for ImageContent {
 }
 
 impl Decodable for ImageContent {
-    fn from_bytes(data: &[u8]) -> Result<Self, String> {
+    fn from_bytes(data: &[u8]) -> Result<Self, Error> {
         if data.len() < 8 {
-            return Err("Not enough data for image dimensions".to_string());
+            return Err(Error::Corrupt(
+                "Not enough data for image dimensions".to_string(),
+            ));
         }
 
         let mut width_bytes = [0u8; 4];
@@ -53,7 +56,7 @@ impl Decodable for ImageContent {
 
         let pixel_data = &data[8..];
         let rgb_image = RgbImage::from_raw(width, height, pixel_data.to_vec())
-            .ok_or_else(|| "Failed to reconstruct image from data".to_string())?;
+            .ok_or_else(|| Error::Corrupt("Failed to reconstruct image from data".to_string()))?;
 
         Ok(Self {
             image: DynamicImage::ImageRgb8(rgb_image),
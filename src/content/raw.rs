@@ -0,0 +1,32 @@
+use crate::traits::{ContentType, Encodable};
+
+/// Already-serialized content, e.g. the `to_bytes` output of another
+/// `Encodable` after it has been wrapped in an envelope.
+pub struct RawContent {
+    pub data: Vec<u8>,
+    pub content_type: ContentType,
+}
+
+impl RawContent {
+    pub fn new(data: Vec<u8>, content_type: ContentType) -> Self {
+        Self { data, content_type }
+    }
+
+    pub fn from_content<T: Encodable + ?Sized>(content: &T) -> Self {
+        Self::new(content.to_bytes(), content.content_type())
+    }
+}
+
+impl Encodable for RawContent {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+
+    fn content_type(&self) -> ContentType {
+        self.content_type
+    }
+
+    fn metadata(&self) -> Vec<u8> {
+        vec![self.content_type.to_u8()]
+    }
+}
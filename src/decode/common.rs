@@ -1,14 +1,50 @@
 use crate::content::audio::AudioContent;
 use crate::content::image::ImageContent;
+use crate::error::Error;
 use crate::traits::{ContentType, Decodable};
 use std::fs as std_fs;
 use std::path::Path as StdPath;
 
+/// Packs bits (0 or 1, most significant bit first) into bytes. Trailing bits
+/// that do not fill a whole byte are ignored.
+pub fn bits_to_bytes(bits: &[u8]) -> Vec<u8> {
+    bits.chunks_exact(8)
+        .map(|byte_bits| byte_bits.iter().fold(0u8, |byte, &bit| (byte << 1) | bit))
+        .collect()
+}
+
+/// Parses a payload built by `encode::utils::payload::build_payload` back into
+/// its data and content type.
+pub fn parse_payload(payload: &[u8]) -> Result<(Vec<u8>, ContentType), Error> {
+    if payload.len() < 1 + 4 {
+        return Err(Error::Corrupt(
+            "Corrupted data: payload too short to contain metadata".to_string(),
+        ));
+    }
+
+    let content_type = ContentType::from_u8(payload[0])
+        .ok_or_else(|| Error::Corrupt(format!("Invalid content type byte: {}", payload[0])))?;
+
+    let mut len_bytes = [0u8; 4];
+    len_bytes.copy_from_slice(&payload[1..5]);
+    let data_len = u32::from_be_bytes(len_bytes) as usize;
+
+    let data = payload.get(5..5 + data_len).ok_or_else(|| {
+        Error::Corrupt(format!(
+            "Corrupted data: claimed data length {} exceeds available payload ({} bytes)",
+            data_len,
+            payload.len() - 5
+        ))
+    })?;
+
+    Ok((data.to_vec(), content_type))
+}
+
 pub fn reconstruct_hidden_content(
     data: &[u8],
     content_type: ContentType,
     output_file_base: &str,
-) -> Result<(), String> {
+) -> Result<(), Error> {
     match content_type {
         ContentType::Image => {
             let image_content = ImageContent::from_bytes(data)?;
@@ -24,7 +60,7 @@ pub fn reconstruct_hidden_content(
             image_content
                 .image
                 .save(&final_output_path)
-                .map_err(|e| format!("Failed to save extracted image: {}", e))?;
+                .map_err(|e| Error::image("Failed to save extracted image", e))?;
             println!("Image successfully extracted to '{}'", final_output_path);
         }
         ContentType::Audio => {
@@ -33,7 +69,7 @@ pub fn reconstruct_hidden_content(
             let final_output_path =
                 format!("{}.{}", output_file_base, audio_content.target_extension);
             std_fs::write(&final_output_path, &audio_content.compressed_audio_bytes)
-                .map_err(|e| format!("Failed to save extracted audio: {}", e))?;
+                .map_err(|e| Error::io("Failed to save extracted audio", e))?;
             println!("Audio successfully extracted to '{}'", final_output_path);
         }
     }
@@ -0,0 +1,33 @@
+use crate::decode::common::parse_payload;
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::append::{find_appended_payload, AppendFormat};
+use std::fs;
+
+/// Looks for a payload stored by `encode_append_to_audio` after the logical end
+/// of the audio file bytes. Returns `Ok(None)` when the format is unsupported or
+/// nothing was appended.
+pub fn extract_append_from_audio(bytes: &[u8]) -> Result<Option<(Vec<u8>, ContentType)>, Error> {
+    let payload = match AppendFormat::from_bytes(bytes) {
+        Some(format @ (AppendFormat::Wav | AppendFormat::Mp3)) => {
+            find_appended_payload(bytes, format)?
+        }
+        _ => None,
+    };
+
+    payload.map(|p| parse_payload(&p)).transpose()
+}
+
+/// Reads the audio file at `steg_audio_path` and looks for a payload with
+/// [`extract_append_from_audio`].
+pub fn decode_append_from_audio(
+    steg_audio_path: &str,
+) -> Result<Option<(Vec<u8>, ContentType)>, Error> {
+    let bytes = fs::read(steg_audio_path).map_err(|e| {
+        Error::io(
+            format!("Failed to read audio file '{}'", steg_audio_path),
+            e,
+        )
+    })?;
+    extract_append_from_audio(&bytes)
+}
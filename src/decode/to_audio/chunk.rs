@@ -0,0 +1,37 @@
+use crate::decode::common::parse_payload;
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::aiff::{self, find_aiff_chunk};
+use crate::utils::audio::AudioFormat;
+use crate::utils::caf::{self, find_caf_chunk};
+use crate::utils::wav::{self, find_wav_chunk};
+use std::fs;
+
+/// Looks for a payload stored by `encode_chunk_to_audio`. Returns `Ok(None)`
+/// when the container is not supported or carries no payload chunk.
+pub fn extract_chunk_from_audio(bytes: &[u8]) -> Result<Option<(Vec<u8>, ContentType)>, Error> {
+    let payload = match AudioFormat::from_bytes(bytes) {
+        Some(AudioFormat::Wav) => find_wav_chunk(bytes, wav::STEG_CHUNK_ID)?,
+        Some(AudioFormat::Aiff) | Some(AudioFormat::Aifc) => {
+            find_aiff_chunk(bytes, aiff::STEG_CHUNK_ID)?
+        }
+        Some(AudioFormat::Caf) => find_caf_chunk(bytes, caf::STEG_CHUNK_ID)?,
+        None => None,
+    };
+
+    payload.map(|p| parse_payload(&p)).transpose()
+}
+
+/// Reads the audio file at `steg_audio_path` and looks for a payload with
+/// [`extract_chunk_from_audio`].
+pub fn decode_chunk_from_audio(
+    steg_audio_path: &str,
+) -> Result<Option<(Vec<u8>, ContentType)>, Error> {
+    let bytes = fs::read(steg_audio_path).map_err(|e| {
+        Error::io(
+            format!("Failed to read audio file '{}'", steg_audio_path),
+            e,
+        )
+    })?;
+    extract_chunk_from_audio(&bytes)
+}
@@ -0,0 +1,22 @@
+use crate::decode::common::parse_payload;
+use crate::encode::utils::deniable::extract_deniable;
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::audio::{read_pcm16, PcmAudio};
+use std::path::Path;
+
+pub fn extract_deniable_from_audio(
+    steg_audio: &PcmAudio,
+    password: &str,
+) -> Result<(Vec<u8>, ContentType), Error> {
+    parse_payload(&extract_deniable(&steg_audio.samples, password)?)
+}
+
+/// Reads the audio file at `steg_audio_path` and extracts its payload with
+/// [`extract_deniable_from_audio`].
+pub fn decode_deniable_from_audio(
+    steg_audio_path: &str,
+    password: &str,
+) -> Result<(Vec<u8>, ContentType), Error> {
+    extract_deniable_from_audio(&read_pcm16(Path::new(steg_audio_path))?, password)
+}
@@ -0,0 +1,75 @@
+use crate::decode::common::{bits_to_bytes, parse_payload};
+use crate::encode::to_audio::echo::EchoParams;
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::audio::{load_carrier_audio, PcmAudio};
+use crate::utils::dsp::{mono_mix, RealFft};
+use rustfft::num_complex::Complex;
+
+const HEADER_BITS: usize = (1 + 4) * 8;
+
+/// Recovers data hidden by echo hiding. Each segment's bit is whichever of
+/// the two echo delays has the larger value in the segment's real cepstrum.
+pub fn extract_echo_from_audio(audio: &PcmAudio) -> Result<(Vec<u8>, ContentType), Error> {
+    let params = EchoParams::for_sample_rate(audio.sample_rate);
+    let signal = mono_mix(&audio.samples, audio.channels);
+    let available_bits = signal.len() / params.segment_len;
+
+    if available_bits < HEADER_BITS {
+        return Err(Error::NotFound(
+            "Audio file too short to contain metadata.".to_string(),
+        ));
+    }
+
+    let fft = RealFft::new(params.segment_len);
+    let read_bits = |range: std::ops::Range<usize>| -> Vec<u8> {
+        range
+            .map(|i| {
+                let segment = &signal[i * params.segment_len..(i + 1) * params.segment_len];
+                let cepstrum = real_cepstrum(&fft, segment);
+                u8::from(cepstrum[params.delays[1]] > cepstrum[params.delays[0]])
+            })
+            .collect()
+    };
+
+    let mut bits = read_bits(0..HEADER_BITS);
+    let header = bits_to_bytes(&bits);
+    let content_type = ContentType::from_u8(header[0]).ok_or_else(|| {
+        Error::Corrupt(format!(
+            "Invalid content type byte extracted: {}",
+            header[0]
+        ))
+    })?;
+    println!("Detected content type from audio: {:?}", content_type);
+
+    let data_len = u32::from_be_bytes(header[1..5].try_into().unwrap()) as usize;
+    let total_bits = data_len
+        .checked_mul(8)
+        .and_then(|n| n.checked_add(HEADER_BITS))
+        .filter(|&n| n <= available_bits)
+        .ok_or_else(|| {
+            Error::Corrupt(format!(
+                "Corrupted data: header declares {} bytes but the audio holds at most {} bits.",
+                data_len, available_bits
+            ))
+        })?;
+
+    bits.extend(read_bits(HEADER_BITS..total_bits));
+    parse_payload(&bits_to_bytes(&bits))
+}
+
+/// Reads the audio file at `steg_audio_path` and extracts its payload with
+/// [`extract_echo_from_audio`]. Compressed input is converted with ffmpeg
+/// first, like carriers on encode.
+pub fn decode_echo_from_audio(steg_audio_path: &str) -> Result<(Vec<u8>, ContentType), Error> {
+    extract_echo_from_audio(&load_carrier_audio(steg_audio_path)?)
+}
+
+fn real_cepstrum(fft: &RealFft, segment: &[f64]) -> Vec<f64> {
+    let log_spectrum = fft
+        .forward(segment)
+        .into_iter()
+        .map(|c| Complex::new((c.norm() + f64::EPSILON).ln(), 0.0))
+        .collect();
+    fft.inverse(log_spectrum)
+}
@@ -0,0 +1,17 @@
+use crate::decode::common::{bits_to_bytes, parse_payload};
+use crate::encode::utils::matrix::extract_matrix;
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::audio::{read_pcm16, PcmAudio};
+use std::path::Path;
+
+pub fn extract_matrix_from_audio(steg_audio: &PcmAudio) -> Result<(Vec<u8>, ContentType), Error> {
+    let bits = extract_matrix(&steg_audio.samples)?;
+    parse_payload(&bits_to_bytes(&bits))
+}
+
+/// Reads the audio file at `steg_audio_path` and extracts its payload with
+/// [`extract_matrix_from_audio`].
+pub fn decode_matrix_from_audio(steg_audio_path: &str) -> Result<(Vec<u8>, ContentType), Error> {
+    extract_matrix_from_audio(&read_pcm16(Path::new(steg_audio_path))?)
+}
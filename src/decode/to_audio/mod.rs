@@ -1 +1,9 @@
+pub mod append;
+pub mod chunk;
+pub mod deniable;
+pub mod echo;
 pub mod lsb;
+pub mod matrix;
+pub mod parity;
+pub mod phase;
+pub mod spread;
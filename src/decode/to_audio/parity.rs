@@ -0,0 +1,57 @@
+use crate::decode::common::{bits_to_bytes, parse_payload};
+use crate::encode::to_audio::parity::region_parity;
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::audio::{load_carrier_audio, PcmAudio};
+
+const HEADER_BITS: usize = (1 + 4) * 8;
+
+pub fn extract_parity_from_audio(
+    steg_audio: &PcmAudio,
+    region_size: usize,
+) -> Result<(Vec<u8>, ContentType), Error> {
+    if region_size == 0 {
+        return Err(Error::InvalidInput(
+            "Region size must be at least 1".to_string(),
+        ));
+    }
+
+    let samples = &steg_audio.samples;
+
+    let bits: Vec<u8> = samples
+        .chunks_exact(region_size)
+        .map(region_parity)
+        .collect();
+    if bits.len() < HEADER_BITS {
+        return Err(Error::NotFound(
+            "Audio file too short to contain metadata.".to_string(),
+        ));
+    }
+
+    let header = bits_to_bytes(&bits[..HEADER_BITS]);
+    let data_len = u32::from_be_bytes(header[1..5].try_into().unwrap()) as usize;
+    let total_bits = data_len
+        .checked_mul(8)
+        .and_then(|n| n.checked_add(HEADER_BITS))
+        .filter(|&n| n <= bits.len())
+        .ok_or_else(|| {
+            Error::Corrupt(format!(
+                "Corrupted data: claimed data length {} exceeds the {} bits available with regions of {} samples.",
+                data_len,
+                bits.len(),
+                region_size
+            ))
+        })?;
+
+    parse_payload(&bits_to_bytes(&bits[..total_bits]))
+}
+
+/// Reads the audio file at `steg_audio_path` and extracts its payload with
+/// [`extract_parity_from_audio`]. Compressed input is converted with ffmpeg
+/// first, like carriers on encode.
+pub fn decode_parity_from_audio(
+    steg_audio_path: &str,
+    region_size: usize,
+) -> Result<(Vec<u8>, ContentType), Error> {
+    extract_parity_from_audio(&load_carrier_audio(steg_audio_path)?, region_size)
+}
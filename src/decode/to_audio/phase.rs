@@ -0,0 +1,82 @@
+use crate::decode::common::bits_to_bytes;
+use crate::encode::to_audio::phase::{
+    phase_segment_len, HEADER_BITS, HEADER_SEGMENT_LEN, MIN_SEGMENT_LEN_LOG2,
+};
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::audio::{load_carrier_audio, PcmAudio};
+use crate::utils::dsp::{channel_samples, RealFft};
+use rustfft::num_complex::Complex;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+// Header bins must lie within this distance of +/-pi/2 for the audio to be
+// considered phase-coded at all.
+const HEADER_PHASE_TOLERANCE: f64 = FRAC_PI_4;
+
+pub fn extract_phase_from_audio(audio: &PcmAudio) -> Result<(Vec<u8>, ContentType), Error> {
+    extract_phase_from_signal(&channel_samples(&audio.samples, audio.channels, 0))
+}
+
+/// Reads the header segment, then the data segment whose length it
+/// declares, and checks the data against the header's CRC-32.
+pub fn extract_phase_from_signal(signal: &[f64]) -> Result<(Vec<u8>, ContentType), Error> {
+    let not_found = || Error::NotFound("No phase-coded data found".to_string());
+    if signal.len() < HEADER_SEGMENT_LEN {
+        return Err(not_found());
+    }
+
+    let spectrum = RealFft::new(HEADER_SEGMENT_LEN).forward(&signal[..HEADER_SEGMENT_LEN]);
+    let header_bins = &spectrum[1..=HEADER_BITS];
+    if header_bins
+        .iter()
+        .any(|c| (c.arg().abs() - FRAC_PI_2).abs() > HEADER_PHASE_TOLERANCE)
+    {
+        return Err(not_found());
+    }
+
+    let header = bits_to_bytes(&phase_bits(header_bins));
+    let content_type = ContentType::from_u8(header[1]).ok_or_else(not_found)?;
+    let data_len = u32::from_be_bytes(header[2..6].try_into().unwrap()) as usize;
+    let checksum = u32::from_be_bytes(header[6..10].try_into().unwrap());
+
+    let segment_len = 1usize
+        .checked_shl(header[0] as u32)
+        .filter(|_| header[0] as u32 >= MIN_SEGMENT_LEN_LOG2)
+        .filter(|&len| data_len.checked_mul(8).map(phase_segment_len) == Some(len))
+        .ok_or_else(|| {
+            Error::Corrupt(format!(
+                "Corrupted data: phase coding header declares {} bytes in a segment of 2^{} samples",
+                data_len, header[0]
+            ))
+        })?;
+    if HEADER_SEGMENT_LEN + segment_len > signal.len() {
+        return Err(Error::Corrupt(format!(
+            "Corrupted data: phase coding header declares a segment of {} samples, but only {} follow the header",
+            segment_len,
+            signal.len() - HEADER_SEGMENT_LEN
+        )));
+    }
+    println!("Detected phase coding segment length: {}", segment_len);
+
+    let data_segment = &signal[HEADER_SEGMENT_LEN..HEADER_SEGMENT_LEN + segment_len];
+    let spectrum = RealFft::new(segment_len).forward(data_segment);
+    let data = bits_to_bytes(&phase_bits(&spectrum[1..=data_len * 8]));
+    if crc32fast::hash(&data) != checksum {
+        return Err(Error::Corrupt(
+            "Corrupted data: phase-coded payload fails its CRC-32 check".to_string(),
+        ));
+    }
+
+    Ok((data, content_type))
+}
+
+/// Reads the audio file at `steg_audio_path` and extracts its payload with
+/// [`extract_phase_from_audio`]. Compressed input is converted with ffmpeg
+/// first, like carriers on encode.
+pub fn decode_phase_from_audio(steg_audio_path: &str) -> Result<(Vec<u8>, ContentType), Error> {
+    extract_phase_from_audio(&load_carrier_audio(steg_audio_path)?)
+}
+
+fn phase_bits(bins: &[Complex<f64>]) -> Vec<u8> {
+    bins.iter().map(|c| u8::from(c.arg() < 0.0)).collect()
+}
@@ -0,0 +1,200 @@
+use crate::decode::common::{bits_to_bytes, parse_payload};
+use crate::encode::to_audio::spread::{pn_chips, MIN_CHIP_RATE};
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::audio::{load_carrier_audio, PcmAudio};
+use crate::utils::dsp::{mono_mix, RealFft};
+
+const HEADER_BITS: usize = (1 + 4) * 8;
+
+// Synchronization only looks at the content type byte. Confidence is computed
+// from the remaining bits, which the offset search has not been tuned to.
+const SYNC_BITS: usize = 8;
+
+// Largest shift (in samples) between the carrier and the watermark that the
+// detector searches for, e.g. encoder delay added when re-encoding to MP3.
+const MAX_SYNC_OFFSET: usize = 4096;
+
+/// A watermark read by [`extract_spread_from_audio`].
+pub struct SpreadWatermark {
+    pub data: Vec<u8>,
+    pub content_type: ContentType,
+    /// Confidence that the bits were read correctly, from 0%
+    /// (indistinguishable from unmarked audio) to 100%.
+    pub confidence: f64,
+    /// Samples the watermark was shifted by in the carrier.
+    pub offset: usize,
+}
+
+/// Correlation detector for [`encode_spread_to_audio`]. The audio is passed
+/// through a first-difference filter before correlating, which suppresses the
+/// low-frequency content that dominates most carriers while keeping the white
+/// pseudo-noise. Returns the detection confidence with the payload.
+///
+/// [`encode_spread_to_audio`]: crate::encode::to_audio::spread::encode_spread_to_audio
+pub fn extract_spread_from_audio(
+    audio: &PcmAudio,
+    key: &str,
+    chip_rate: usize,
+) -> Result<SpreadWatermark, Error> {
+    if chip_rate < MIN_CHIP_RATE {
+        return Err(Error::InvalidInput(format!(
+            "Chip rate must be at least {}, got {}",
+            MIN_CHIP_RATE, chip_rate
+        )));
+    }
+
+    let signal = first_difference(&mono_mix(&audio.samples, audio.channels));
+    if signal.len() < HEADER_BITS * chip_rate {
+        return Err(Error::NotFound(
+            "Audio file too short to contain metadata.".to_string(),
+        ));
+    }
+
+    let header_chips = pn_chips(key, HEADER_BITS * chip_rate);
+    let offset = sync_offset(&signal, &header_chips[..SYNC_BITS * chip_rate], chip_rate);
+    let signal = &signal[offset..];
+    let available_bits = signal.len() / chip_rate;
+    if available_bits < HEADER_BITS {
+        return Err(Error::NotFound(
+            "Audio file too short to contain metadata.".to_string(),
+        ));
+    }
+
+    let header_scores = bit_scores(signal, &header_chips, chip_rate);
+    let header = bits_to_bytes(&scores_to_bits(&header_scores));
+    let data_len = u32::from_be_bytes(header[1..5].try_into().unwrap()) as usize;
+    let total_bits = data_len
+        .checked_mul(8)
+        .and_then(|n| n.checked_add(HEADER_BITS))
+        .filter(|&n| n <= available_bits);
+    let (Some(_), Some(total_bits)) = (ContentType::from_u8(header[0]), total_bits) else {
+        return Err(Error::NotFound(format!(
+            "No spread-spectrum watermark detected with this key and chip rate (header confidence {:.1}%)",
+            confidence(&header_scores[SYNC_BITS..])
+        )));
+    };
+    if offset > 0 {
+        println!("Watermark synchronized at an offset of {} samples", offset);
+    }
+
+    let chips = pn_chips(key, total_bits * chip_rate);
+    let scores = bit_scores(signal, &chips, chip_rate);
+    let confidence = confidence(&scores[SYNC_BITS..]);
+    println!(
+        "Detection confidence: {:.1}% over {} bits (weakest bit z-score {:.2})",
+        confidence,
+        scores.len(),
+        scores.iter().map(|z| z.abs()).fold(f64::INFINITY, f64::min)
+    );
+
+    let (data, content_type) = parse_payload(&bits_to_bytes(&scores_to_bits(&scores)))?;
+    Ok(SpreadWatermark {
+        data,
+        content_type,
+        confidence,
+        offset,
+    })
+}
+
+/// Reads the audio file at `steg_audio_path` and extracts its payload with
+/// [`extract_spread_from_audio`].
+pub fn decode_spread_from_audio(
+    steg_audio_path: &str,
+    key: &str,
+    chip_rate: usize,
+) -> Result<SpreadWatermark, Error> {
+    extract_spread_from_audio(&load_carrier_audio(steg_audio_path)?, key, chip_rate)
+}
+
+// Normalized correlation of each bit's segment with its chips, scaled by
+// sqrt(chip_rate) so that unmarked audio scores roughly N(0, 1).
+fn bit_scores(signal: &[f64], chips: &[f64], chip_rate: usize) -> Vec<f64> {
+    signal
+        .chunks_exact(chip_rate)
+        .zip(chips.chunks_exact(chip_rate))
+        .map(|(segment, pn)| {
+            let dot: f64 = segment.iter().zip(pn).map(|(x, p)| x * p).sum();
+            let energy: f64 =
+                segment.iter().map(|x| x * x).sum::<f64>() * pn.iter().map(|p| p * p).sum::<f64>();
+            if energy == 0.0 {
+                0.0
+            } else {
+                dot / energy.sqrt() * (chip_rate as f64).sqrt()
+            }
+        })
+        .collect()
+}
+
+fn scores_to_bits(scores: &[f64]) -> Vec<u8> {
+    scores.iter().map(|&z| u8::from(z > 0.0)).collect()
+}
+
+// Finds the shift of `sync_chips` that maximizes the summed correlation
+// magnitude of the bits they cover, using FFT cross-correlation per bit. The
+// chips are differenced like the signal; raw chips would correlate as
+// strongly one sample late as at the true offset.
+fn sync_offset(signal: &[f64], sync_chips: &[f64], chip_rate: usize) -> usize {
+    let max_offset = MAX_SYNC_OFFSET.min(signal.len() - sync_chips.len());
+    if max_offset == 0 {
+        return 0;
+    }
+
+    let fft = RealFft::new((chip_rate + max_offset).next_power_of_two());
+    let mut totals = vec![0.0; max_offset + 1];
+    for (i, pn) in sync_chips.chunks_exact(chip_rate).enumerate() {
+        let start = i * chip_rate;
+        let window = &signal[start..start + chip_rate + max_offset];
+        let spectrum = fft
+            .forward(window)
+            .into_iter()
+            .zip(fft.forward(&first_difference(pn)))
+            .map(|(s, p)| s * p.conj())
+            .collect();
+        let correlation = fft.inverse(spectrum);
+        for (total, c) in totals.iter_mut().zip(&correlation) {
+            *total += c.abs();
+        }
+    }
+
+    totals
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map_or(0, |(offset, _)| offset)
+}
+
+fn first_difference(signal: &[f64]) -> Vec<f64> {
+    let mut previous = 0.0;
+    signal
+        .iter()
+        .map(|&x| {
+            let diff = x - previous;
+            previous = x;
+            diff
+        })
+        .collect()
+}
+
+// Confidence that the bits were read correctly, from 0% (indistinguishable
+// from unmarked audio) to 100%. Marked bits score +/-mu plus unit noise, so
+// mu is estimated from the mean squared score, less two standard deviations
+// of that mean on unmarked audio, and turned into a bit error probability.
+fn confidence(scores: &[f64]) -> f64 {
+    let n = scores.len() as f64;
+    let mean_square = scores.iter().map(|z| z * z).sum::<f64>() / n;
+    let noise_floor = 1.0 + 2.0 * (2.0 / n).sqrt();
+    let mu = (mean_square - noise_floor).max(0.0).sqrt();
+    let error_rate = 0.5 * erfc(mu / 2f64.sqrt());
+    100.0 * (1.0 - 2.0 * error_rate)
+}
+
+// Abramowitz and Stegun 7.1.26, accurate to about 1.5e-7 for x >= 0.
+fn erfc(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    poly * (-x * x).exp()
+}
@@ -0,0 +1,39 @@
+use crate::decode::common::{bits_to_bytes, parse_payload};
+use crate::encode::utils::lsb::LsbSample;
+use crate::encode::utils::texture::{texture_mask, PREAMBLE_PIXELS, THRESHOLD_BITS};
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::load::load_image;
+use image::RgbImage;
+
+pub fn extract_adaptive_from_image(steg_image: &RgbImage) -> Result<(Vec<u8>, ContentType), Error> {
+    let steg_bytes = steg_image.as_flat_samples().samples;
+    if steg_bytes.len() < PREAMBLE_PIXELS * 3 {
+        return Err(Error::NotFound(
+            "Image too small to contain hidden data".to_string(),
+        ));
+    }
+
+    let threshold_bits: Vec<u8> = steg_bytes[..THRESHOLD_BITS]
+        .iter()
+        .map(|v| v.lsb())
+        .collect();
+    let threshold = bits_to_bytes(&threshold_bits)[0];
+    println!("Detected texture threshold: {}", threshold);
+
+    let bits: Vec<u8> = texture_mask(steg_image, threshold)
+        .iter()
+        .enumerate()
+        .filter(|&(_, &textured)| textured)
+        .flat_map(|(pixel, _)| &steg_bytes[pixel * 3..pixel * 3 + 3])
+        .map(|v| v.lsb())
+        .collect();
+
+    parse_payload(&bits_to_bytes(&bits))
+}
+
+/// Reads the image at `steg_file` and extracts its payload with
+/// [`extract_adaptive_from_image`].
+pub fn decode_adaptive(steg_file: &str) -> Result<(Vec<u8>, ContentType), Error> {
+    extract_adaptive_from_image(&load_image(steg_file)?.to_rgb8())
+}
@@ -0,0 +1,27 @@
+use crate::decode::common::parse_payload;
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::append::{find_appended_payload, AppendFormat};
+use std::fs;
+
+/// Looks for a payload stored by `encode_append` after the logical end of
+/// the image bytes. Returns `Ok(None)` when the format is unsupported or nothing was
+/// appended.
+pub fn extract_append_from_image(bytes: &[u8]) -> Result<Option<(Vec<u8>, ContentType)>, Error> {
+    let payload = match AppendFormat::from_bytes(bytes) {
+        Some(format @ (AppendFormat::Png | AppendFormat::Jpeg | AppendFormat::Gif)) => {
+            find_appended_payload(bytes, format)?
+        }
+        _ => None,
+    };
+
+    payload.map(|p| parse_payload(&p)).transpose()
+}
+
+/// Reads the image at `steg_file` and looks for a payload with
+/// [`extract_append_from_image`].
+pub fn decode_append(steg_file: &str) -> Result<Option<(Vec<u8>, ContentType)>, Error> {
+    let bytes = fs::read(steg_file)
+        .map_err(|e| Error::io(format!("Failed to read image '{}'", steg_file), e))?;
+    extract_append_from_image(&bytes)
+}
@@ -0,0 +1,43 @@
+use crate::decode::common::{bits_to_bytes, parse_payload};
+use crate::encode::to_image::bpcs::{
+    complexity, conjugate, validate_threshold, BitPlanes, DATA_BITS_PER_BLOCK,
+};
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::load::load_image;
+use image::RgbImage;
+
+pub fn extract_bpcs_from_image(
+    steg_image: &RgbImage,
+    threshold: f64,
+) -> Result<(Vec<u8>, ContentType), Error> {
+    validate_threshold(threshold)?;
+
+    let planes = BitPlanes::new(
+        steg_image.as_flat_samples().samples,
+        steg_image.width() as usize,
+        steg_image.height() as usize,
+    );
+
+    let bits: Vec<u8> = planes
+        .positions()
+        .map(|position| planes.block(position))
+        .filter(|&block| complexity(block) >= threshold)
+        .flat_map(|block| {
+            let block = if block & 1 == 1 {
+                conjugate(block)
+            } else {
+                block
+            };
+            (1..=DATA_BITS_PER_BLOCK).map(move |i| ((block >> i) & 1) as u8)
+        })
+        .collect();
+
+    parse_payload(&bits_to_bytes(&bits))
+}
+
+/// Reads the image at `steg_file` and extracts its payload with
+/// [`extract_bpcs_from_image`].
+pub fn decode_bpcs(steg_file: &str, threshold: f64) -> Result<(Vec<u8>, ContentType), Error> {
+    extract_bpcs_from_image(&load_image(steg_file)?.to_rgb8(), threshold)
+}
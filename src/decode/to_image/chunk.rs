@@ -0,0 +1,28 @@
+use crate::decode::common::parse_payload;
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::jpeg::{find_jpeg_app_segments, is_jpeg, STEG_APP_IDENTIFIER};
+use crate::utils::png::{find_png_chunk, is_png, STEG_CHUNK_ID};
+use std::fs;
+
+/// Looks for a payload stored by `encode_chunk`. Returns `Ok(None)` when the
+/// image bytes are not a PNG/JPEG or carries no payload chunk.
+pub fn extract_chunk_from_image(bytes: &[u8]) -> Result<Option<(Vec<u8>, ContentType)>, Error> {
+    let payload = if is_png(bytes) {
+        find_png_chunk(bytes, STEG_CHUNK_ID)?.map(|data| data.to_vec())
+    } else if is_jpeg(bytes) {
+        find_jpeg_app_segments(bytes, STEG_APP_IDENTIFIER)?
+    } else {
+        None
+    };
+
+    payload.map(|p| parse_payload(&p)).transpose()
+}
+
+/// Reads the image at `steg_file` and looks for a payload with
+/// [`extract_chunk_from_image`].
+pub fn decode_chunk(steg_file: &str) -> Result<Option<(Vec<u8>, ContentType)>, Error> {
+    let bytes = fs::read(steg_file)
+        .map_err(|e| Error::io(format!("Failed to read image '{}'", steg_file), e))?;
+    extract_chunk_from_image(&bytes)
+}
@@ -0,0 +1,22 @@
+use crate::decode::common::parse_payload;
+use crate::encode::utils::deniable::extract_deniable;
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::load::load_image;
+use image::RgbImage;
+
+pub fn extract_deniable_from_image(
+    steg_image: &RgbImage,
+    password: &str,
+) -> Result<(Vec<u8>, ContentType), Error> {
+    parse_payload(&extract_deniable(
+        steg_image.as_flat_samples().samples,
+        password,
+    )?)
+}
+
+/// Reads the image at `steg_file` and extracts its payload with
+/// [`extract_deniable_from_image`].
+pub fn decode_deniable(steg_file: &str, password: &str) -> Result<(Vec<u8>, ContentType), Error> {
+    extract_deniable_from_image(&load_image(steg_file)?.to_rgb8(), password)
+}
@@ -0,0 +1,101 @@
+use crate::decode::common::{bits_to_bytes, parse_payload};
+use crate::encode::to_image::dwt::{
+    canonical_coefficients, data_repetition, SlotLayout, HEADER_BITS, HEADER_REPETITION,
+    MIN_DATA_REPETITION,
+};
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::load::load_image;
+use image::RgbImage;
+
+/// A watermark read by [`extract_dwt_from_image`].
+pub struct DwtWatermark {
+    pub data: Vec<u8>,
+    pub content_type: ContentType,
+    /// Blocks whose own bit disagreed with the majority vote over all the
+    /// blocks carrying that bit.
+    pub disagreements: usize,
+    /// Blocks read, header included.
+    pub blocks: usize,
+    /// Blocks carrying each data bit.
+    pub repetition: usize,
+}
+
+impl DwtWatermark {
+    /// Fraction of blocks read wrong before the vote.
+    pub fn bit_error_rate(&self) -> f64 {
+        self.disagreements as f64 / self.blocks as f64
+    }
+}
+
+/// Reads a DWT watermark by soft majority vote over each bit's blocks and
+/// reports the raw bit error rate: how many blocks disagreed with the vote.
+///
+/// The vote is the only error correction, so a bit is lost once half of its
+/// blocks are. Cropping is not survived: the decoder rescales the whole image
+/// to the canonical size, so cutting off even a single row or column moves
+/// every block.
+pub fn extract_dwt_from_image(steg_image: &RgbImage, key: &str) -> Result<DwtWatermark, Error> {
+    let plane = canonical_coefficients(steg_image);
+    let layout = SlotLayout::new(key);
+
+    let mut disagreements = 0;
+    let mut vote = |slots: &mut dyn Iterator<Item = usize>, bit_count: usize| -> Vec<u8> {
+        let soft: Vec<(usize, f64)> = slots
+            .enumerate()
+            .map(|(offset, slot)| (offset % bit_count, layout.soft_bit(&plane, slot)))
+            .collect();
+        let mut sums = vec![0.0; bit_count];
+        for &(bit, value) in &soft {
+            sums[bit] += value;
+        }
+        let bits: Vec<u8> = sums.iter().map(|&sum| u8::from(sum < 0.0)).collect();
+        disagreements += soft
+            .iter()
+            .filter(|&&(bit, value)| u8::from(value < 0.0) != bits[bit])
+            .count();
+        bits
+    };
+
+    let header_slots = HEADER_BITS * HEADER_REPETITION;
+    let mut bits = vote(&mut (0..header_slots), HEADER_BITS);
+    let header = bits_to_bytes(&bits);
+    let data_bits = u32::from_be_bytes(header[1..5].try_into().unwrap()) as usize * 8;
+    if ContentType::from_u8(header[0]).is_none()
+        || data_bits == 0
+        || data_repetition(layout.slot_count(), data_bits) < MIN_DATA_REPETITION
+    {
+        return Err(Error::NotFound(
+            "No DWT watermark found with this key".to_string(),
+        ));
+    }
+
+    let repetition = data_repetition(layout.slot_count(), data_bits);
+    bits.extend(vote(
+        &mut (header_slots..header_slots + data_bits * repetition),
+        data_bits,
+    ));
+
+    let (data, content_type) = parse_payload(&bits_to_bytes(&bits))?;
+    let watermark = DwtWatermark {
+        data,
+        content_type,
+        disagreements,
+        blocks: header_slots + data_bits * repetition,
+        repetition,
+    };
+    println!(
+        "Raw bit error rate: {:.2}% ({} of {} blocks disagree with the majority vote, {}x repetition)",
+        100.0 * watermark.bit_error_rate(),
+        watermark.disagreements,
+        watermark.blocks,
+        watermark.repetition
+    );
+    Ok(watermark)
+}
+
+/// Reads the image at `steg_file` and extracts its payload with
+/// [`extract_dwt_from_image`].
+pub fn decode_dwt(steg_file: &str, key: &str) -> Result<DwtWatermark, Error> {
+    extract_dwt_from_image(&load_image(steg_file)?.to_rgb8(), key)
+}
@@ -0,0 +1,17 @@
+use crate::decode::common::{bits_to_bytes, parse_payload};
+use crate::encode::utils::matrix::extract_matrix;
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::load::load_image;
+use image::RgbImage;
+
+pub fn extract_matrix_from_image(steg_image: &RgbImage) -> Result<(Vec<u8>, ContentType), Error> {
+    let bits = extract_matrix(steg_image.as_flat_samples().samples)?;
+    parse_payload(&bits_to_bytes(&bits))
+}
+
+/// Reads the image at `steg_file` and extracts its payload with
+/// [`extract_matrix_from_image`].
+pub fn decode_matrix(steg_file: &str) -> Result<(Vec<u8>, ContentType), Error> {
+    extract_matrix_from_image(&load_image(steg_file)?.to_rgb8())
+}
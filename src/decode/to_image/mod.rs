@@ -1 +1,10 @@
+pub mod adaptive;
+pub mod append;
+pub mod bpcs;
+pub mod chunk;
+pub mod deniable;
+pub mod dwt;
 pub mod lsb;
+pub mod matrix;
+pub mod pvd;
+pub mod stc;
@@ -0,0 +1,29 @@
+use crate::decode::common::{bits_to_bytes, parse_payload};
+use crate::encode::to_image::pvd::{pair_indices, pair_usable, range_for, to_avg_diff};
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::load::load_image;
+use image::RgbImage;
+
+pub fn extract_pvd_from_image(steg_image: &RgbImage) -> Result<(Vec<u8>, ContentType), Error> {
+    let steg_bytes = steg_image.as_flat_samples().samples;
+
+    let mut bits = Vec::new();
+    for (a, b) in pair_indices(steg_bytes.len()) {
+        let (avg, diff) = to_avg_diff(steg_bytes[a], steg_bytes[b]);
+        if !pair_usable(avg, diff) {
+            continue;
+        }
+        let (lower, bit_count) = range_for(diff);
+        let value = diff.abs() - lower;
+        bits.extend((0..bit_count).rev().map(|i| ((value >> i) & 1) as u8));
+    }
+
+    parse_payload(&bits_to_bytes(&bits))
+}
+
+/// Reads the image at `steg_file` and extracts its payload with
+/// [`extract_pvd_from_image`].
+pub fn decode_pvd(steg_file: &str) -> Result<(Vec<u8>, ContentType), Error> {
+    extract_pvd_from_image(&load_image(steg_file)?.to_rgb8())
+}
@@ -0,0 +1,40 @@
+use crate::decode::common::{bits_to_bytes, parse_payload};
+use crate::encode::to_image::stc::PREAMBLE_SAMPLES;
+use crate::encode::utils::lsb::LsbSample;
+use crate::encode::utils::stc::stc_extract;
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::load::load_image;
+use image::RgbImage;
+
+pub fn extract_stc_from_image(steg_image: &RgbImage) -> Result<(Vec<u8>, ContentType), Error> {
+    let lsbs: Vec<u8> = steg_image
+        .as_flat_samples()
+        .samples
+        .iter()
+        .map(|v| v.lsb())
+        .collect();
+
+    if lsbs.len() < PREAMBLE_SAMPLES {
+        return Err(Error::NotFound(
+            "Image too small to contain hidden data".to_string(),
+        ));
+    }
+    let (preamble, stego) = lsbs.split_at(PREAMBLE_SAMPLES);
+    let message_len = u32::from_be_bytes(bits_to_bytes(preamble).try_into().unwrap()) as usize;
+    if message_len == 0 || message_len > stego.len() {
+        return Err(Error::Corrupt(format!(
+            "Corrupted data: message length {} bits does not fit the {} available samples",
+            message_len,
+            stego.len()
+        )));
+    }
+
+    parse_payload(&bits_to_bytes(&stc_extract(stego, message_len)))
+}
+
+/// Reads the image at `steg_file` and extracts its payload with
+/// [`extract_stc_from_image`].
+pub fn decode_stc(steg_file: &str) -> Result<(Vec<u8>, ContentType), Error> {
+    extract_stc_from_image(&load_image(steg_file)?.to_rgb8())
+}
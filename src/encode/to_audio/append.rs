@@ -0,0 +1,100 @@
+use crate::encode::utils::payload::build_payload;
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::append::{append_payload, AppendFormat};
+use std::fs;
+
+/// Appends the payload after the logical end of a WAV (RIFF chunk) or MP3
+/// (last frame / ID3v1 tag) carrier given as the bytes of the file,
+/// optionally as a ZIP polyglot. The audio data itself is copied unchanged.
+pub fn embed_append_in_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &[u8],
+    as_zip: bool,
+) -> Result<Vec<u8>, Error> {
+    let payload = build_payload(hide_content);
+
+    let format = match AppendFormat::from_bytes(carrier) {
+        Some(f @ (AppendFormat::Wav | AppendFormat::Mp3)) => f,
+        _ => {
+            return Err(Error::Format(
+                "Carrier audio must be a WAV or MP3 file for append embedding.".to_string(),
+            ))
+        }
+    };
+
+    append_payload(carrier, format, &payload, as_zip)
+}
+
+/// Hides `hide_content` in the audio file at `carrier_audio_path_str` with
+/// [`embed_append_in_audio`] and writes the result to `output_audio_path_str`.
+pub fn encode_append_to_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier_audio_path_str: &str,
+    output_audio_path_str: &str,
+    as_zip: bool,
+) -> Result<(), Error> {
+    let carrier = fs::read(carrier_audio_path_str).map_err(|e| {
+        Error::io(
+            format!("Failed to read carrier audio '{}'", carrier_audio_path_str),
+            e,
+        )
+    })?;
+
+    let output = embed_append_in_audio(hide_content, &carrier, as_zip)?;
+    fs::write(output_audio_path_str, output).map_err(|e| {
+        Error::io(
+            format!("Failed to write output audio '{}'", output_audio_path_str),
+            e,
+        )
+    })?;
+
+    println!(
+        "Successfully appended data after the end of '{}', output to '{}'",
+        carrier_audio_path_str, output_audio_path_str
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_audio::append::extract_append_from_audio;
+    use crate::testing::{noise_audio, secret};
+    use crate::utils::audio::AudioFormat;
+
+    #[test]
+    fn round_trips_after_wav() {
+        let carrier = noise_audio(500).to_bytes(AudioFormat::Wav).unwrap();
+        let hidden = secret(200);
+        for as_zip in [false, true] {
+            let stego = embed_append_in_audio(&hidden, &carrier, as_zip).unwrap();
+            assert!(stego.starts_with(&carrier));
+            let (data, _) = extract_append_from_audio(&stego).unwrap().unwrap();
+            assert_eq!(data, hidden.data);
+        }
+        assert!(extract_append_from_audio(&carrier).unwrap().is_none());
+    }
+
+    #[test]
+    fn round_trips_after_streamed_wav() {
+        let carrier = noise_audio(500).to_bytes(AudioFormat::Wav).unwrap();
+        let mut streamed = carrier.clone();
+        streamed[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        streamed[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let stego = embed_append_in_audio(&secret(200), &streamed, false).unwrap();
+        assert!(stego.starts_with(&carrier));
+        let (data, _) = extract_append_from_audio(&stego).unwrap().unwrap();
+        assert_eq!(data, secret(200).data);
+    }
+
+    #[test]
+    fn rejects_other_containers() {
+        let carrier = noise_audio(500).to_bytes(AudioFormat::Caf).unwrap();
+        assert!(matches!(
+            embed_append_in_audio(&secret(4), &carrier, false),
+            Err(Error::Format(_))
+        ));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::encode::utils::payload::build_payload;
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::aiff::{self, insert_aiff_chunk};
+use crate::utils::audio::AudioFormat;
+use crate::utils::caf::{self, insert_caf_chunk};
+use crate::utils::wav::{self, insert_wav_chunk};
+use std::fs;
+use std::path::Path;
+
+/// Stores the payload in a custom chunk of a WAV, AIFF/AIFF-C or CAF carrier
+/// given as the bytes of the file. No samples are modified and the output
+/// keeps the carrier's container.
+pub fn embed_chunk_in_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let payload = build_payload(hide_content);
+
+    match AudioFormat::from_bytes(carrier) {
+        Some(AudioFormat::Wav) => insert_wav_chunk(carrier, wav::STEG_CHUNK_ID, &payload),
+        Some(AudioFormat::Aiff | AudioFormat::Aifc) => {
+            insert_aiff_chunk(carrier, aiff::STEG_CHUNK_ID, &payload)
+        }
+        Some(AudioFormat::Caf) => insert_caf_chunk(carrier, caf::STEG_CHUNK_ID, &payload),
+        None => Err(Error::Format(
+            "Carrier audio must be a WAV, AIFF, AIFF-C or CAF file for chunk embedding."
+                .to_string(),
+        )),
+    }
+}
+
+/// Hides `hide_content` in the audio file at `carrier_audio_path_str` with
+/// [`embed_chunk_in_audio`] and writes the result to `output_audio_path_str`.
+pub fn encode_chunk_to_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier_audio_path_str: &str,
+    output_audio_path_str: &str,
+) -> Result<(), Error> {
+    let carrier = fs::read(carrier_audio_path_str).map_err(|e| {
+        Error::io(
+            format!("Failed to read carrier audio '{}'", carrier_audio_path_str),
+            e,
+        )
+    })?;
+
+    let output_format = AudioFormat::from_path(Path::new(output_audio_path_str));
+    match AudioFormat::from_bytes(&carrier) {
+        Some(carrier_format) if output_format != Some(carrier_format) => {
+            return Err(Error::InvalidInput(format!(
+                "Output audio path '{}' must use the same container as the carrier ({:?}).",
+                output_audio_path_str, carrier_format
+            )));
+        }
+        _ => {}
+    }
+
+    let output = embed_chunk_in_audio(hide_content, &carrier)?;
+    fs::write(output_audio_path_str, output).map_err(|e| {
+        Error::io(
+            format!("Failed to write output audio '{}'", output_audio_path_str),
+            e,
+        )
+    })?;
+
+    println!(
+        "Successfully stored data from input carrier '{}' in a metadata chunk of '{}'",
+        carrier_audio_path_str, output_audio_path_str
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_audio::chunk::extract_chunk_from_audio;
+    use crate::testing::{noise_audio, secret};
+    use crate::utils::audio::PcmAudio;
+
+    #[test]
+    fn round_trips_in_every_container() {
+        let audio = noise_audio(1000);
+        let hidden = secret(301);
+        for format in [
+            AudioFormat::Wav,
+            AudioFormat::Aiff,
+            AudioFormat::Aifc,
+            AudioFormat::Caf,
+        ] {
+            let stego = embed_chunk_in_audio(&hidden, &audio.to_bytes(format).unwrap()).unwrap();
+            assert_eq!(PcmAudio::from_bytes(&stego).unwrap().samples, audio.samples);
+            let (data, _) = extract_chunk_from_audio(&stego).unwrap().unwrap();
+            assert_eq!(data, hidden.data);
+        }
+    }
+
+    #[test]
+    fn finds_nothing_in_clean_audio() {
+        let carrier = noise_audio(100).to_bytes(AudioFormat::Wav).unwrap();
+        assert!(extract_chunk_from_audio(&carrier).unwrap().is_none());
+    }
+}
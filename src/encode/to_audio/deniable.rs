@@ -0,0 +1,62 @@
+use crate::content::raw::RawContent;
+use crate::encode::utils::deniable::{embed_deniable, SLOT_COUNT};
+use crate::encode::utils::payload::build_payload;
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::audio::{load_carrier_audio, write_pcm16, AudioFormat, PcmAudio};
+use std::path::Path;
+
+/// Encrypts the content, and optionally a decoy, into separate password
+/// slots spread over every sample's LSB.
+pub fn embed_deniable_in_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut PcmAudio,
+    password: &str,
+    decoy: Option<(&RawContent, &str)>,
+) -> Result<(), Error> {
+    let payload = build_payload(hide_content);
+    let decoy_payload = decoy.map(|(content, password)| (build_payload(content), password));
+    let mut payloads = vec![(payload.as_slice(), password)];
+    payloads.extend(
+        decoy_payload
+            .as_ref()
+            .map(|(payload, password)| (payload.as_slice(), *password)),
+    );
+
+    embed_deniable(&mut carrier.samples, &payloads)?;
+    println!(
+        "Filled {} password slots of {} samples each",
+        SLOT_COUNT,
+        carrier.samples.len() / SLOT_COUNT
+    );
+
+    Ok(())
+}
+
+/// Hides `hide_content` in the audio file at `carrier_audio_path_str` with
+/// [`embed_deniable_in_audio`] and writes the result to `output_audio_path_str`.
+pub fn encode_deniable_to_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier_audio_path_str: &str,
+    output_audio_path_str: &str,
+    password: &str,
+    decoy: Option<(&RawContent, &str)>,
+) -> Result<(), Error> {
+    let final_output_path = Path::new(output_audio_path_str);
+    if AudioFormat::from_path(final_output_path).is_none() {
+        return Err(Error::InvalidInput(format!(
+            "Output audio path '{}' must have a .wav, .aiff, .aifc or .caf extension. Deniable embedding on audio produces uncompressed PCM.",
+            output_audio_path_str
+        )));
+    }
+
+    let mut audio = load_carrier_audio(carrier_audio_path_str)?;
+    embed_deniable_in_audio(hide_content, &mut audio, password, decoy)?;
+    write_pcm16(final_output_path, &audio)?;
+
+    println!(
+        "Successfully hid data from input carrier '{}', output to audio file '{}'",
+        carrier_audio_path_str, output_audio_path_str
+    );
+    Ok(())
+}
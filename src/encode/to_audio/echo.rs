@@ -0,0 +1,195 @@
+use crate::encode::utils::payload::{build_payload, bytes_to_bits};
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::audio::{load_carrier_audio, write_pcm16, AudioFormat, PcmAudio};
+use crate::utils::dsp::{channel_samples, set_channel_samples};
+use std::path::Path;
+
+// Parameters are defined at 44.1 kHz and scaled to the carrier's sample rate,
+// so the echoes keep their duration if the file is resampled.
+const REFERENCE_SAMPLE_RATE: u64 = 44_100;
+const REFERENCE_SEGMENT_LEN: u64 = 1024;
+const REFERENCE_DELAYS: [u64; 2] = [48, 64];
+
+// Number of samples over which the mixer fades between the two echoes at a
+// segment boundary, as a fraction of the segment length.
+const MIXER_RAMP_DIVISOR: usize = 8;
+
+/// Segment length and echo delays (for a 0 bit and a 1 bit) used at a given
+/// sample rate.
+pub struct EchoParams {
+    pub segment_len: usize,
+    pub delays: [usize; 2],
+}
+
+impl EchoParams {
+    pub fn for_sample_rate(sample_rate: u32) -> Self {
+        let scale = |reference: u64| {
+            ((reference * sample_rate as u64 + REFERENCE_SAMPLE_RATE / 2) / REFERENCE_SAMPLE_RATE)
+                .max(1) as usize
+        };
+        Self {
+            segment_len: scale(REFERENCE_SEGMENT_LEN),
+            delays: REFERENCE_DELAYS.map(scale),
+        }
+    }
+}
+
+/// Hides one bit per segment by adding a faint echo of the signal, delayed by
+/// `delays[0]` for a 0 bit or `delays[1]` for a 1 bit. The two echo kernels
+/// are blended by a smoothed mixer signal so segment boundaries do not click.
+/// `amplitude` is the echo level relative to the signal, in (0, 1]; the
+/// decoder only compares the two delays, so it need not know it.
+pub fn embed_echo_in_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut PcmAudio,
+    amplitude: f64,
+) -> Result<(), Error> {
+    if !(amplitude > 0.0 && amplitude <= 1.0) {
+        return Err(Error::InvalidInput(format!(
+            "Echo amplitude must be in (0, 1], got {}",
+            amplitude
+        )));
+    }
+
+    let bits = bytes_to_bits(&build_payload(hide_content));
+
+    let params = EchoParams::for_sample_rate(carrier.sample_rate);
+    let frames = carrier.samples.len() / carrier.channels.max(1) as usize;
+
+    let available_bits = frames / params.segment_len;
+    if bits.len() > available_bits {
+        return Err(Error::Capacity(format!(
+            "Not enough space in carrier audio. Echo hiding needs {} segments of {} samples, available {}.",
+            bits.len(),
+            params.segment_len,
+            available_bits
+        )));
+    }
+
+    let mixers = smoothed_mixers(&bits, params.segment_len, frames);
+
+    for channel in 0..carrier.channels as usize {
+        let signal = channel_samples(&carrier.samples, carrier.channels, channel);
+        let mut echoed = signal.clone();
+        for (n, value) in echoed.iter_mut().enumerate() {
+            for (mixer, &delay) in mixers.iter().zip(&params.delays) {
+                if mixer[n] > 0.0 && n >= delay {
+                    *value += amplitude * mixer[n] * signal[n - delay];
+                }
+            }
+        }
+        set_channel_samples(&mut carrier.samples, carrier.channels, channel, &echoed);
+    }
+
+    Ok(())
+}
+
+/// Hides `hide_content` in the audio file at `carrier_audio_path_str` with
+/// [`embed_echo_in_audio`] and writes the result to `output_audio_path_str`.
+pub fn encode_echo_to_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier_audio_path_str: &str,
+    output_audio_path_str: &str,
+    amplitude: f64,
+) -> Result<(), Error> {
+    let final_output_path = Path::new(output_audio_path_str);
+    if AudioFormat::from_path(final_output_path).is_none() {
+        return Err(Error::InvalidInput(format!(
+            "Output audio path '{}' must have a .wav, .aiff, .aifc or .caf extension. Echo hiding produces uncompressed PCM.",
+            output_audio_path_str
+        )));
+    }
+
+    let mut audio = load_carrier_audio(carrier_audio_path_str)?;
+    embed_echo_in_audio(hide_content, &mut audio, amplitude)?;
+    write_pcm16(final_output_path, &audio)?;
+
+    println!(
+        "Successfully echo-hid data from input carrier '{}', output to audio file '{}'",
+        carrier_audio_path_str, output_audio_path_str
+    );
+    Ok(())
+}
+
+// Per-sample weights of the 0-bit and 1-bit echoes. Each is a step function
+// following the bits, smoothed with a moving average so the weights ramp
+// linearly across segment boundaries. Samples after the last bit get no echo.
+fn smoothed_mixers(bits: &[u8], segment_len: usize, frames: usize) -> [Vec<f64>; 2] {
+    let ramp = (segment_len / MIXER_RAMP_DIVISOR).max(1);
+    [0u8, 1u8].map(|bit| {
+        let mut steps = vec![0.0; frames];
+        for (i, _) in bits.iter().enumerate().filter(|&(_, &b)| b == bit) {
+            steps[i * segment_len..(i + 1) * segment_len].fill(1.0);
+        }
+
+        let mut prefix = Vec::with_capacity(frames + 1);
+        prefix.push(0.0);
+        for &s in &steps {
+            prefix.push(prefix.last().unwrap() + s);
+        }
+        (0..frames)
+            .map(|n| {
+                let start = n.saturating_sub(ramp / 2);
+                let end = (n + ramp - ramp / 2).min(frames);
+                (prefix[end] - prefix[start]) / (end - start) as f64
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_audio::echo::extract_echo_from_audio;
+    use crate::testing::{noise_audio, requantize, resample, secret};
+
+    fn echoed() -> PcmAudio {
+        let mut audio = noise_audio(110 * 1024);
+        embed_echo_in_audio(&secret(8), &mut audio, 0.3).unwrap();
+        audio
+    }
+
+    #[test]
+    fn round_trip() {
+        let (data, _) = extract_echo_from_audio(&echoed()).unwrap();
+        assert_eq!(data, secret(8).data);
+    }
+
+    #[test]
+    fn survives_resampling() {
+        let audio = echoed();
+        let (data, _) = extract_echo_from_audio(&resample(&audio, 48_000)).unwrap();
+        assert_eq!(data, secret(8).data);
+
+        let there_and_back = resample(&resample(&audio, 48_000), 44_100);
+        let (data, _) = extract_echo_from_audio(&there_and_back).unwrap();
+        assert_eq!(data, secret(8).data);
+    }
+
+    #[test]
+    fn survives_requantization() {
+        let (data, _) = extract_echo_from_audio(&requantize(&echoed(), 8)).unwrap();
+        assert_eq!(data, secret(8).data);
+    }
+
+    #[test]
+    fn scales_with_sample_rate() {
+        let params = EchoParams::for_sample_rate(88_200);
+        assert_eq!(params.segment_len, 2048);
+        assert_eq!(params.delays, [96, 128]);
+    }
+
+    #[test]
+    fn rejects_bad_amplitude_and_short_carrier() {
+        let mut audio = noise_audio(10 * 1024);
+        assert!(matches!(
+            embed_echo_in_audio(&secret(8), &mut audio, 1.5),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            embed_echo_in_audio(&secret(8), &mut audio, 0.3),
+            Err(Error::Capacity(_))
+        ));
+    }
+}
@@ -0,0 +1,58 @@
+use crate::encode::utils::matrix::{choose_k, embed_matrix};
+use crate::encode::utils::payload::{build_payload, bytes_to_bits};
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::audio::{load_carrier_audio, write_pcm16, AudioFormat, PcmAudio};
+use std::path::Path;
+
+/// LSB embedding through a (1, 2^k - 1, k) Hamming code, with `k` picked
+/// from the payload to capacity ratio.
+pub fn embed_matrix_in_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut PcmAudio,
+) -> Result<(), Error> {
+    let bits = bytes_to_bits(&build_payload(hide_content));
+
+    let k = choose_k(bits.len(), carrier.samples.len()).ok_or_else(|| {
+        Error::Capacity(format!(
+            "Not enough space in carrier audio. Needed {} bits, available {} samples.",
+            bits.len(),
+            carrier.samples.len()
+        ))
+    })?;
+    let changed = embed_matrix(&mut carrier.samples, &bits, k);
+    println!(
+        "Matrix embedding with k = {}: {} samples changed for {} bits",
+        k,
+        changed,
+        bits.len()
+    );
+
+    Ok(())
+}
+
+/// Hides `hide_content` in the audio file at `carrier_audio_path_str` with
+/// [`embed_matrix_in_audio`] and writes the result to `output_audio_path_str`.
+pub fn encode_matrix_to_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier_audio_path_str: &str,
+    output_audio_path_str: &str,
+) -> Result<(), Error> {
+    let final_output_path = Path::new(output_audio_path_str);
+    if AudioFormat::from_path(final_output_path).is_none() {
+        return Err(Error::InvalidInput(format!(
+            "Output audio path '{}' must have a .wav, .aiff, .aifc or .caf extension. Matrix embedding on audio produces uncompressed PCM.",
+            output_audio_path_str
+        )));
+    }
+
+    let mut audio = load_carrier_audio(carrier_audio_path_str)?;
+    embed_matrix_in_audio(hide_content, &mut audio)?;
+    write_pcm16(final_output_path, &audio)?;
+
+    println!(
+        "Successfully hid data from input carrier '{}', output to audio file '{}'",
+        carrier_audio_path_str, output_audio_path_str
+    );
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use crate::encode::utils::payload::{build_payload, bytes_to_bits};
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::audio::{load_carrier_audio, write_pcm16, AudioFormat, PcmAudio};
+use std::path::Path;
+
+/// Hides each bit as the parity of the LSBs of a region of `region_size`
+/// consecutive samples. When the parity is wrong, only the least perceptible
+/// sample of the region is changed by one step: the loudest one, nudged
+/// toward the average of its neighbours.
+pub fn embed_parity_in_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut PcmAudio,
+    region_size: usize,
+) -> Result<(), Error> {
+    if region_size == 0 {
+        return Err(Error::InvalidInput(
+            "Region size must be at least 1".to_string(),
+        ));
+    }
+
+    let bits = bytes_to_bits(&build_payload(hide_content));
+
+    let available_bits = carrier.samples.len() / region_size;
+    if bits.len() > available_bits {
+        return Err(Error::Capacity(format!(
+            "Not enough space in carrier audio. Needed {} bits, available {} bits with regions of {} samples.",
+            bits.len(),
+            available_bits,
+            region_size
+        )));
+    }
+
+    let original = carrier.samples.clone();
+    let mut modified_count = 0;
+    for (region_index, &bit) in bits.iter().enumerate() {
+        let start = region_index * region_size;
+        let region = &original[start..start + region_size];
+        if region_parity(region) == bit {
+            continue;
+        }
+
+        let (offset, _) = region
+            .iter()
+            .enumerate()
+            .max_by_key(|&(_, &s)| (s as i32).abs())
+            .unwrap();
+        let index = start + offset;
+        carrier.samples[index] = flip_lsb_toward_neighbours(&original, index);
+        modified_count += 1;
+    }
+    println!(
+        "Parity coding modified {} of {} samples",
+        modified_count,
+        bits.len() * region_size
+    );
+
+    Ok(())
+}
+
+/// Hides `hide_content` in the audio file at `carrier_audio_path_str` with
+/// [`embed_parity_in_audio`] and writes the result to `output_audio_path_str`.
+pub fn encode_parity_to_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier_audio_path_str: &str,
+    output_audio_path_str: &str,
+    region_size: usize,
+) -> Result<(), Error> {
+    let final_output_path = Path::new(output_audio_path_str);
+    if AudioFormat::from_path(final_output_path).is_none() {
+        return Err(Error::InvalidInput(format!(
+            "Output audio path '{}' must have a .wav, .aiff, .aifc or .caf extension. Parity coding produces uncompressed PCM.",
+            output_audio_path_str
+        )));
+    }
+
+    let mut audio = load_carrier_audio(carrier_audio_path_str)?;
+    embed_parity_in_audio(hide_content, &mut audio, region_size)?;
+    write_pcm16(final_output_path, &audio)?;
+
+    println!(
+        "Successfully parity-coded data from input carrier '{}', output to audio file '{}'",
+        carrier_audio_path_str, output_audio_path_str
+    );
+    Ok(())
+}
+
+pub fn region_parity(region: &[i16]) -> u8 {
+    region.iter().fold(0, |parity, &s| parity ^ (s & 1) as u8)
+}
+
+// Changes the sample by +/-1, toward the mean of its neighbours so the
+// waveform gets smoother rather than noisier, and never past the i16 range.
+fn flip_lsb_toward_neighbours(samples: &[i16], index: usize) -> i16 {
+    let sample = samples[index];
+    if sample == i16::MAX {
+        return sample - 1;
+    }
+    if sample == i16::MIN {
+        return sample + 1;
+    }
+
+    let previous = samples[index.saturating_sub(1)] as i32;
+    let next = *samples.get(index + 1).unwrap_or(&sample) as i32;
+    if 2 * sample as i32 > previous + next {
+        sample - 1
+    } else {
+        sample + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_audio::parity::extract_parity_from_audio;
+    use crate::testing::{noise_audio, secret};
+
+    #[test]
+    fn round_trip_changes_one_sample_per_region_at_most() {
+        let hidden = secret(50);
+        for region_size in [1, 4, 7] {
+            let original = noise_audio(2000);
+            let mut audio = noise_audio(2000);
+            embed_parity_in_audio(&hidden, &mut audio, region_size).unwrap();
+
+            for (before, after) in original
+                .samples
+                .chunks(region_size)
+                .zip(audio.samples.chunks(region_size))
+            {
+                let changed: Vec<i32> = before
+                    .iter()
+                    .zip(after)
+                    .map(|(&b, &a)| (a as i32 - b as i32).abs())
+                    .filter(|&d| d != 0)
+                    .collect();
+                assert!(changed.is_empty() || changed == [1]);
+            }
+
+            let (data, _) = extract_parity_from_audio(&audio, region_size).unwrap();
+            assert_eq!(data, hidden.data);
+        }
+    }
+
+    #[test]
+    fn stays_in_range_at_full_scale() {
+        let mut audio = PcmAudio::new(1, 44100, vec![i16::MAX; 400]);
+        embed_parity_in_audio(&secret(4), &mut audio, 4).unwrap();
+        let (data, _) = extract_parity_from_audio(&audio, 4).unwrap();
+        assert_eq!(data, secret(4).data);
+    }
+
+    #[test]
+    fn rejects_empty_regions_and_short_carrier() {
+        let mut audio = noise_audio(10);
+        assert!(matches!(
+            embed_parity_in_audio(&secret(4), &mut audio, 0),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            embed_parity_in_audio(&secret(4), &mut audio, 4),
+            Err(Error::Capacity(_))
+        ));
+    }
+
+    #[test]
+    fn reports_an_oversized_length_as_corrupt() {
+        // Every region odd: the header claims 0xFFFFFFFF bytes.
+        let audio = PcmAudio::new(1, 44100, vec![1; 400]);
+        assert!(matches!(
+            extract_parity_from_audio(&audio, 1),
+            Err(Error::Corrupt(_))
+        ));
+    }
+}
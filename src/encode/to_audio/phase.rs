@@ -0,0 +1,216 @@
+use crate::decode::to_audio::phase::extract_phase_from_signal;
+use crate::encode::utils::payload::bytes_to_bits;
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::audio::{load_carrier_audio, write_pcm16, AudioFormat, PcmAudio};
+use crate::utils::dsp::{channel_samples, set_channel_samples, RealFft};
+use rustfft::num_complex::Complex;
+use std::f64::consts::FRAC_PI_2;
+use std::path::Path;
+
+pub const MIN_SEGMENT_LEN_LOG2: u32 = 10;
+
+/// The header is phase-coded on its own in a first segment of this length,
+/// so that the decoder learns the data segment length before reading it.
+pub const HEADER_SEGMENT_LEN: usize = 1 << MIN_SEGMENT_LEN_LOG2;
+
+/// Data segment length (log2), content type, data length (u32, big-endian)
+/// and CRC-32 of the data.
+pub const HEADER_BITS: usize = (1 + 1 + 4 + 4) * 8;
+
+// Bins carrying data are raised to at least this magnitude (relative to the
+// square root of the segment length) so their phase survives rounding the
+// reconstructed signal to 16-bit samples.
+const MIN_MAGNITUDE_FACTOR: f64 = 4.0;
+
+/// Segment length used to phase-code `total_bits` bits: the smallest power of
+/// two whose spectrum has a bin (excluding DC and Nyquist) for every bit.
+pub fn phase_segment_len(total_bits: usize) -> usize {
+    (2 * (total_bits + 1))
+        .next_power_of_two()
+        .max(1 << MIN_SEGMENT_LEN_LOG2)
+}
+
+/// Hides the payload in the phase of spectral components of the first
+/// channel (+pi/2 for a 0 bit, -pi/2 for a 1 bit): the header in a first
+/// segment of [`HEADER_SEGMENT_LEN`] samples, the data in the segment after
+/// it. Every later segment is shifted so that the phase difference between
+/// consecutive segments matches the original signal. The result is decoded
+/// again and rejected if rounding to 16-bit samples lost any bit.
+pub fn embed_phase_in_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut PcmAudio,
+) -> Result<(), Error> {
+    let data = hide_content.to_bytes();
+    let data_bits = bytes_to_bits(&data);
+
+    let signal = channel_samples(&carrier.samples, carrier.channels, 0);
+
+    let segment_len = phase_segment_len(data_bits.len());
+    if HEADER_SEGMENT_LEN + segment_len > signal.len() {
+        return Err(Error::Capacity(format!(
+            "Not enough space in carrier audio. Phase coding {} bits needs {} samples per channel, available {}.",
+            data_bits.len(),
+            HEADER_SEGMENT_LEN + segment_len,
+            signal.len()
+        )));
+    }
+    println!("Using phase coding segment length {}", segment_len);
+
+    let mut header = Vec::with_capacity(HEADER_BITS / 8);
+    header.push(segment_len.trailing_zeros() as u8);
+    header.push(hide_content.content_type().to_u8());
+    header.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    header.extend_from_slice(&crc32fast::hash(&data).to_be_bytes());
+
+    let mut modified_signal = signal;
+    let (header_region, data_region) = modified_signal.split_at_mut(HEADER_SEGMENT_LEN);
+    phase_code(header_region, HEADER_SEGMENT_LEN, &bytes_to_bits(&header));
+    phase_code(data_region, segment_len, &data_bits);
+
+    let stored: Vec<f64> = modified_signal
+        .iter()
+        .map(|value| value.round().clamp(i16::MIN as f64, i16::MAX as f64))
+        .collect();
+    match extract_phase_from_signal(&stored) {
+        Ok((decoded, _)) if decoded == data => {}
+        _ => {
+            return Err(Error::Capacity(format!(
+                "Phase coding cannot hide {} bytes reliably in this carrier; rounding to 16-bit samples would corrupt them. Use a longer or louder carrier.",
+                data.len()
+            )))
+        }
+    }
+
+    set_channel_samples(&mut carrier.samples, carrier.channels, 0, &stored);
+
+    Ok(())
+}
+
+/// Phase-codes `bits` into the first `segment_len` samples of `signal` and
+/// shifts every later whole segment to keep its original phase difference to
+/// the segment before.
+fn phase_code(signal: &mut [f64], segment_len: usize, bits: &[u8]) {
+    let fft = RealFft::new(segment_len);
+    let half = segment_len / 2;
+    let min_magnitude = MIN_MAGNITUDE_FACTOR * (segment_len as f64).sqrt();
+
+    let mut prev_original_phase = vec![0.0; half + 1];
+    let mut prev_new_phase = vec![0.0; half + 1];
+
+    for (segment_index, segment) in signal.chunks_exact_mut(segment_len).enumerate() {
+        let spectrum = fft.forward(segment);
+        let mut new_spectrum = spectrum.clone();
+
+        for k in 0..=half {
+            let (mut magnitude, phase) = spectrum[k].to_polar();
+            let new_phase = if segment_index > 0 {
+                prev_new_phase[k] + (phase - prev_original_phase[k])
+            } else if (1..=bits.len()).contains(&k) {
+                magnitude = magnitude.max(min_magnitude);
+                if bits[k - 1] == 0 {
+                    FRAC_PI_2
+                } else {
+                    -FRAC_PI_2
+                }
+            } else {
+                phase
+            };
+            prev_original_phase[k] = phase;
+            prev_new_phase[k] = new_phase;
+
+            new_spectrum[k] = Complex::from_polar(magnitude, new_phase);
+            if k > 0 && k < half {
+                new_spectrum[segment_len - k] = new_spectrum[k].conj();
+            }
+        }
+
+        let mut coded = fft.inverse(new_spectrum);
+        fit_to_i16(&mut coded);
+        segment.copy_from_slice(&coded);
+    }
+}
+
+// Aligned phases can build peaks past the 16-bit range, which clipping would
+// turn into bit errors. Scaling the whole segment keeps every phase.
+fn fit_to_i16(segment: &mut [f64]) {
+    let peak = segment
+        .iter()
+        .fold(0.0f64, |peak, value| peak.max(value.abs()));
+    let limit = i16::MAX as f64;
+    if peak > limit {
+        let scale = limit / peak;
+        segment.iter_mut().for_each(|value| *value *= scale);
+    }
+}
+
+/// Hides `hide_content` in the audio file at `carrier_audio_path_str` with
+/// [`embed_phase_in_audio`] and writes the result to `output_audio_path_str`.
+pub fn encode_phase_to_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier_audio_path_str: &str,
+    output_audio_path_str: &str,
+) -> Result<(), Error> {
+    let final_output_path = Path::new(output_audio_path_str);
+    if AudioFormat::from_path(final_output_path).is_none() {
+        return Err(Error::InvalidInput(format!(
+            "Output audio path '{}' must have a .wav, .aiff, .aifc or .caf extension. Phase coding produces uncompressed PCM.",
+            output_audio_path_str
+        )));
+    }
+
+    let mut audio = load_carrier_audio(carrier_audio_path_str)?;
+    embed_phase_in_audio(hide_content, &mut audio)?;
+    write_pcm16(final_output_path, &audio)?;
+
+    println!(
+        "Successfully phase-coded data from input carrier '{}', output to audio file '{}'",
+        carrier_audio_path_str, output_audio_path_str
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_audio::phase::extract_phase_from_audio;
+    use crate::testing::{noise_audio, secret};
+
+    #[test]
+    fn round_trip() {
+        let mut audio = noise_audio(8192);
+        let hidden = secret(100);
+        embed_phase_in_audio(&hidden, &mut audio).unwrap();
+        let (data, _) = extract_phase_from_audio(&audio).unwrap();
+        assert_eq!(data, hidden.data);
+    }
+
+    #[test]
+    fn detects_damaged_data() {
+        let mut audio = noise_audio(8192);
+        embed_phase_in_audio(&secret(100), &mut audio).unwrap();
+        // The data segment follows the header segment.
+        audio.samples[2 * HEADER_SEGMENT_LEN..4 * HEADER_SEGMENT_LEN].reverse();
+        assert!(matches!(
+            extract_phase_from_audio(&audio),
+            Err(Error::Corrupt(_))
+        ));
+    }
+
+    #[test]
+    fn finds_nothing_in_clean_audio() {
+        assert!(matches!(
+            extract_phase_from_audio(&noise_audio(8192)),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_a_short_carrier() {
+        let mut audio = noise_audio(HEADER_SEGMENT_LEN);
+        assert!(matches!(
+            embed_phase_in_audio(&secret(100), &mut audio),
+            Err(Error::Capacity(_))
+        ));
+    }
+}
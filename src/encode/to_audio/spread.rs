@@ -0,0 +1,221 @@
+use crate::encode::utils::payload::{build_payload, bytes_to_bits};
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::audio::{load_carrier_audio, write_pcm16, AudioFormat, PcmAudio};
+use crate::utils::dsp::{channel_samples, mono_mix, set_channel_samples};
+use crate::utils::prng::keyed_rng;
+use rand::Rng;
+use std::path::Path;
+
+const PN_CONTEXT: &str = "spread-spectrum";
+
+pub const MIN_CHIP_RATE: usize = 64;
+
+// Floor for the chip amplitude so bits landing in silence are still
+// recoverable.
+const MIN_CHIP_AMPLITUDE: f64 = 2.0;
+
+pub struct SpreadOptions<'a> {
+    /// Secret seed for the pseudo-noise sequence.
+    pub key: &'a str,
+    /// Number of chips (samples) each bit is spread over.
+    pub chip_rate: usize,
+    /// Chip amplitude relative to the RMS level of the segment it is added to.
+    pub strength: f64,
+}
+
+/// The keyed pseudo-noise sequence as +/-1 chips.
+pub fn pn_chips(key: &str, count: usize) -> Vec<f64> {
+    let mut rng = keyed_rng(PN_CONTEXT, key);
+    (0..count)
+        .map(|_| if rng.gen_bool(0.5) { 1.0 } else { -1.0 })
+        .collect()
+}
+
+/// Direct-sequence spread spectrum: each bit modulates the sign of
+/// `chip_rate` chips of a keyed pseudo-noise sequence, which is added at a
+/// level proportional to the local signal and identically on every channel.
+pub fn embed_spread_in_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut PcmAudio,
+    options: &SpreadOptions,
+) -> Result<(), Error> {
+    if options.chip_rate < MIN_CHIP_RATE {
+        return Err(Error::InvalidInput(format!(
+            "Chip rate must be at least {}, got {}",
+            MIN_CHIP_RATE, options.chip_rate
+        )));
+    }
+    if !(options.strength.is_finite() && options.strength > 0.0) {
+        return Err(Error::InvalidInput(format!(
+            "Strength must be a positive number, got {}",
+            options.strength
+        )));
+    }
+
+    let bits = bytes_to_bits(&build_payload(hide_content));
+
+    let mono = mono_mix(&carrier.samples, carrier.channels);
+
+    let available_bits = mono.len() / options.chip_rate;
+    if bits.len() > available_bits {
+        let seconds = |samples: usize| samples as f64 / carrier.sample_rate.max(1) as f64;
+        let needed = bits.len() * options.chip_rate;
+        let fitting_chip_rate = mono.len() / bits.len();
+        let hint = if fitting_chip_rate >= MIN_CHIP_RATE {
+            format!(
+                "Use a carrier of at least {:.1} s or a --chip-rate of at most {} (less robust).",
+                seconds(needed),
+                fitting_chip_rate
+            )
+        } else {
+            format!("Use a carrier of at least {:.1} s.", seconds(needed))
+        };
+        return Err(Error::Capacity(format!(
+            "Not enough space in carrier audio. Spread spectrum at chip rate {} needs {} samples per channel ({:.1} s), available {} ({:.1} s). {}",
+            options.chip_rate,
+            needed,
+            seconds(needed),
+            mono.len(),
+            seconds(mono.len()),
+            hint
+        )));
+    }
+
+    let chips = pn_chips(options.key, bits.len() * options.chip_rate);
+    let mut watermark = vec![0.0; mono.len()];
+    for (i, &bit) in bits.iter().enumerate() {
+        let range = i * options.chip_rate..(i + 1) * options.chip_rate;
+        let rms = (mono[range.clone()].iter().map(|x| x * x).sum::<f64>()
+            / options.chip_rate as f64)
+            .sqrt();
+        let amplitude = (options.strength * rms).max(MIN_CHIP_AMPLITUDE);
+        let sign = if bit == 1 { 1.0 } else { -1.0 };
+        for n in range {
+            watermark[n] = sign * amplitude * chips[n];
+        }
+    }
+
+    for channel in 0..carrier.channels as usize {
+        let mut signal = channel_samples(&carrier.samples, carrier.channels, channel);
+        for (value, w) in signal.iter_mut().zip(&watermark) {
+            *value += w;
+        }
+        set_channel_samples(&mut carrier.samples, carrier.channels, channel, &signal);
+    }
+    println!(
+        "Spread {} bits over {} samples",
+        bits.len(),
+        bits.len() * options.chip_rate
+    );
+
+    Ok(())
+}
+
+/// Hides `hide_content` in the audio file at `carrier_audio_path_str` with
+/// [`embed_spread_in_audio`] and writes the result to `output_audio_path_str`.
+pub fn encode_spread_to_audio<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier_audio_path_str: &str,
+    output_audio_path_str: &str,
+    options: &SpreadOptions,
+) -> Result<(), Error> {
+    let final_output_path = Path::new(output_audio_path_str);
+    if AudioFormat::from_path(final_output_path).is_none() {
+        return Err(Error::InvalidInput(format!(
+            "Output audio path '{}' must have a .wav, .aiff, .aifc or .caf extension. Spread spectrum produces uncompressed PCM.",
+            output_audio_path_str
+        )));
+    }
+
+    let mut audio = load_carrier_audio(carrier_audio_path_str)?;
+    embed_spread_in_audio(hide_content, &mut audio, options)?;
+    write_pcm16(final_output_path, &audio)?;
+
+    println!(
+        "Successfully spread data from input carrier '{}', output to audio file '{}'",
+        carrier_audio_path_str, output_audio_path_str
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_audio::spread::extract_spread_from_audio;
+    use crate::testing::{noise_audio, requantize, resample, secret};
+
+    const OPTIONS: SpreadOptions = SpreadOptions {
+        key: "correct horse",
+        chip_rate: 2048,
+        strength: 0.25,
+    };
+
+    fn watermarked() -> PcmAudio {
+        let mut audio = noise_audio(80 * 2048);
+        embed_spread_in_audio(&secret(4), &mut audio, &OPTIONS).unwrap();
+        audio
+    }
+
+    #[test]
+    fn round_trip() {
+        let watermark = extract_spread_from_audio(&watermarked(), OPTIONS.key, 2048).unwrap();
+        assert_eq!(watermark.data, secret(4).data);
+        assert_eq!(watermark.offset, 0);
+        assert!(watermark.confidence > 99.0, "{}", watermark.confidence);
+    }
+
+    #[test]
+    fn round_trip_at_default_options() {
+        // The --chip-rate and --strength defaults.
+        let options = SpreadOptions {
+            chip_rate: 4096,
+            strength: 0.05,
+            ..OPTIONS
+        };
+        let mut audio = noise_audio(80 * 4096);
+        embed_spread_in_audio(&secret(4), &mut audio, &options).unwrap();
+        let watermark = extract_spread_from_audio(&audio, options.key, 4096).unwrap();
+        assert_eq!(watermark.data, secret(4).data);
+    }
+
+    #[test]
+    fn survives_requantization_and_resampling() {
+        let audio = watermarked();
+        let requantized = requantize(&audio, 8);
+        let watermark = extract_spread_from_audio(&requantized, OPTIONS.key, 2048).unwrap();
+        assert_eq!(watermark.data, secret(4).data);
+
+        let resampled = resample(&resample(&audio, 48_000), 44_100);
+        let watermark = extract_spread_from_audio(&resampled, OPTIONS.key, 2048).unwrap();
+        assert_eq!(watermark.data, secret(4).data);
+    }
+
+    #[test]
+    fn finds_shifted_watermark() {
+        let audio = watermarked();
+        let mut samples = vec![0; 2 * 1000];
+        samples.extend_from_slice(&audio.samples);
+        let shifted = PcmAudio::new(2, 44100, samples);
+        let watermark = extract_spread_from_audio(&shifted, OPTIONS.key, 2048).unwrap();
+        assert_eq!(watermark.data, secret(4).data);
+        assert_eq!(watermark.offset, 1000);
+    }
+
+    #[test]
+    fn wrong_key_finds_nothing() {
+        assert!(matches!(
+            extract_spread_from_audio(&watermarked(), "wrong key", 2048),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_a_short_carrier() {
+        let mut audio = noise_audio(10 * 2048);
+        assert!(matches!(
+            embed_spread_in_audio(&secret(4), &mut audio, &OPTIONS),
+            Err(Error::Capacity(_))
+        ));
+    }
+}
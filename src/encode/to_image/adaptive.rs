@@ -0,0 +1,112 @@
+use crate::encode::utils::lsb::LsbSample;
+use crate::encode::utils::payload::{build_payload, bytes_to_bits};
+use crate::encode::utils::texture::{texture_mask, THRESHOLD_BITS};
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::load::{load_image, save_image};
+use image::RgbImage;
+
+/// LSB replacement restricted to textured pixels, where changes are hardest
+/// to notice. The texture threshold is stored in the first pixels so the
+/// decoder can rebuild the same mask.
+pub fn embed_adaptive_in_image<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut RgbImage,
+    threshold: u8,
+) -> Result<(), Error> {
+    let payload = build_payload(hide_content);
+    let bits = bytes_to_bits(&payload);
+
+    let mask = texture_mask(carrier, threshold);
+    let textured_pixels = mask.iter().filter(|&&textured| textured).count();
+    if bits.len() > textured_pixels * 3 {
+        return Err(Error::Capacity(format!(
+            "Not enough textured area: {} of {} pixels reach texture threshold {}, holding {} bits, but {} bits are needed. Use a lower --texture-threshold or a busier carrier.",
+            textured_pixels,
+            mask.len(),
+            threshold,
+            textured_pixels * 3,
+            bits.len()
+        )));
+    }
+    println!(
+        "Embedding in {} textured pixels ({:.1}% of the image)",
+        textured_pixels,
+        100.0 * textured_pixels as f64 / mask.len() as f64
+    );
+
+    let steg_image_bytes = carrier.as_flat_samples_mut().samples;
+    let threshold_bits = bytes_to_bits(&[threshold]);
+    let textured_samples = mask
+        .iter()
+        .enumerate()
+        .filter(|&(_, &textured)| textured)
+        .flat_map(|(pixel, _)| pixel * 3..pixel * 3 + 3);
+    let positions = (0..THRESHOLD_BITS).chain(textured_samples);
+    for (index, &bit) in positions.zip(threshold_bits.iter().chain(&bits)) {
+        steg_image_bytes[index] = steg_image_bytes[index].with_lsb(bit);
+    }
+
+    Ok(())
+}
+
+/// Hides `hide_content` in the image at `steg_file` with
+/// [`embed_adaptive_in_image`] and saves the result to `output_file`.
+pub fn encode_adaptive<T: Encodable + ?Sized>(
+    hide_content: &T,
+    steg_file: &str,
+    output_file: &str,
+    threshold: u8,
+) -> Result<(), Error> {
+    let mut carrier = load_image(steg_file)?.to_rgb8();
+    embed_adaptive_in_image(hide_content, &mut carrier, threshold)?;
+    save_image(&carrier, output_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_image::adaptive::extract_adaptive_from_image;
+    use crate::encode::utils::texture::PREAMBLE_PIXELS;
+    use crate::testing::{noise_image, secret};
+
+    // Noise on the left half, flat grey on the right.
+    fn half_textured() -> RgbImage {
+        let noise = noise_image(64, 64);
+        RgbImage::from_fn(64, 64, |x, y| {
+            if x < 32 {
+                *noise.get_pixel(x, y)
+            } else {
+                image::Rgb([128, 128, 128])
+            }
+        })
+    }
+
+    #[test]
+    fn round_trip_leaves_flat_areas_alone() {
+        let hidden = secret(200);
+        let original = half_textured();
+        let mut image = original.clone();
+        embed_adaptive_in_image(&hidden, &mut image, 24).unwrap();
+
+        assert_eq!(texture_mask(&image, 24), texture_mask(&original, 24));
+        // Pixels two or more away from the noise have no gradient.
+        for (x, y, pixel) in image.enumerate_pixels() {
+            if x > 33 && (y * 64 + x) as usize >= PREAMBLE_PIXELS {
+                assert_eq!(pixel, original.get_pixel(x, y));
+            }
+        }
+
+        let (data, _) = extract_adaptive_from_image(&image).unwrap();
+        assert_eq!(data, hidden.data);
+    }
+
+    #[test]
+    fn rejects_a_flat_image() {
+        let mut image = RgbImage::from_pixel(64, 64, image::Rgb([90, 90, 90]));
+        assert!(matches!(
+            embed_adaptive_in_image(&secret(10), &mut image, 24),
+            Err(Error::Capacity(_))
+        ));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::encode::utils::payload::build_payload;
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::append::{append_payload, AppendFormat};
+use std::fs;
+
+/// Appends the payload after the logical end (IEND, EOI or trailer) of a
+/// PNG, JPEG or GIF carrier given as the bytes of the file, optionally as a
+/// ZIP polyglot. The image data itself is copied unchanged.
+pub fn embed_append_in_image<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &[u8],
+    as_zip: bool,
+) -> Result<Vec<u8>, Error> {
+    let payload = build_payload(hide_content);
+
+    let format = match AppendFormat::from_bytes(carrier) {
+        Some(f @ (AppendFormat::Png | AppendFormat::Jpeg | AppendFormat::Gif)) => f,
+        _ => {
+            return Err(Error::Format(
+                "Carrier image must be a PNG, JPEG or GIF file for append embedding.".to_string(),
+            ))
+        }
+    };
+
+    append_payload(carrier, format, &payload, as_zip)
+}
+
+/// Hides `hide_content` in the image at `steg_file` with
+/// [`embed_append_in_image`] and writes the result to `output_file`.
+pub fn encode_append<T: Encodable + ?Sized>(
+    hide_content: &T,
+    steg_file: &str,
+    output_file: &str,
+    as_zip: bool,
+) -> Result<(), Error> {
+    let carrier = fs::read(steg_file)
+        .map_err(|e| Error::io(format!("Failed to read carrier image '{}'", steg_file), e))?;
+
+    let output = embed_append_in_image(hide_content, &carrier, as_zip)?;
+    fs::write(output_file, output).map_err(|e| Error::io("Failed to save output image", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_image::append::extract_append_from_image;
+    use crate::testing::{image_bytes, noise_image, secret};
+    use image::ImageFormat;
+
+    #[test]
+    fn round_trips_after_png_jpeg_and_gif() {
+        let image = noise_image(16, 16);
+        let hidden = secret(500);
+        for format in [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::Gif] {
+            let carrier = image_bytes(&image, format);
+            for as_zip in [false, true] {
+                let stego = embed_append_in_image(&hidden, &carrier, as_zip).unwrap();
+                assert!(stego.starts_with(&carrier));
+                let (data, _) = extract_append_from_image(&stego).unwrap().unwrap();
+                assert_eq!(data, hidden.data);
+            }
+        }
+    }
+
+    #[test]
+    fn replaces_an_earlier_payload() {
+        let carrier = image_bytes(&noise_image(16, 16), ImageFormat::Png);
+        let first = embed_append_in_image(&secret(900), &carrier, true).unwrap();
+        let second = embed_append_in_image(&secret(10), &first, false).unwrap();
+        let (data, _) = extract_append_from_image(&second).unwrap().unwrap();
+        assert_eq!(data, secret(10).data);
+        assert!(extract_append_from_image(&carrier).unwrap().is_none());
+    }
+}
@@ -0,0 +1,258 @@
+use crate::encode::utils::payload::{build_payload, bytes_to_bits};
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::load::{load_image, save_image};
+use image::RgbImage;
+
+pub const BLOCK_SIZE: usize = 8;
+const BIT_PLANES: u32 = 8;
+
+/// Each embedded block carries 63 payload bits; bit (0, 0) records whether
+/// the block was conjugated.
+pub const DATA_BITS_PER_BLOCK: usize = 63;
+
+/// Checkerboard pattern with (0, 0) set, so conjugating a block whose flag
+/// bit is clear sets it.
+const CHECKERBOARD: u64 = 0xAA55_AA55_AA55_AA55;
+
+// Border length of an 8x8 block: 7 horizontal plus 7 vertical neighbours in
+// each of 8 rows and columns.
+const MAX_BORDER: f64 = 112.0;
+
+/// Checks a complexity threshold is usable. Above 0.5, conjugating a simple
+/// payload block would not be guaranteed to make it complex.
+pub fn validate_threshold(threshold: f64) -> Result<(), Error> {
+    if threshold > 0.0 && threshold <= 0.5 {
+        Ok(())
+    } else {
+        Err(Error::InvalidInput(format!(
+            "Complexity threshold must be in (0, 0.5], got {}",
+            threshold
+        )))
+    }
+}
+
+/// Fraction of adjacent bit pairs in the block that differ. Bit `r * 8 + c`
+/// holds row `r`, column `c`.
+pub fn complexity(block: u64) -> f64 {
+    const NOT_LAST_COLUMN: u64 = 0x7F7F_7F7F_7F7F_7F7F;
+    let horizontal = ((block ^ (block >> 1)) & NOT_LAST_COLUMN).count_ones();
+    let vertical = ((block ^ (block >> 8)) & (u64::MAX >> 8)).count_ones();
+    (horizontal + vertical) as f64 / MAX_BORDER
+}
+
+pub fn conjugate(block: u64) -> u64 {
+    block ^ CHECKERBOARD
+}
+
+fn to_gray(value: u8) -> u8 {
+    value ^ (value >> 1)
+}
+
+fn from_gray(gray: u8) -> u8 {
+    let mut value = gray;
+    value ^= value >> 1;
+    value ^= value >> 2;
+    value ^= value >> 4;
+    value
+}
+
+/// Bit-planes of an RGB image in canonical Gray code, addressed as 8x8
+/// blocks. Gray coding keeps a change in one plane from rippling into the
+/// others.
+pub struct BitPlanes {
+    gray: Vec<u8>,
+    width: usize,
+    blocks_x: usize,
+    blocks_y: usize,
+}
+
+/// Position of a block: bit-plane (0 is least significant), colour channel
+/// and block coordinates.
+#[derive(Clone, Copy)]
+pub struct BlockPosition {
+    plane: u32,
+    channel: usize,
+    x: usize,
+    y: usize,
+}
+
+impl BitPlanes {
+    pub fn new(samples: &[u8], width: usize, height: usize) -> Self {
+        Self {
+            gray: samples.iter().map(|&v| to_gray(v)).collect(),
+            width,
+            blocks_x: width / BLOCK_SIZE,
+            blocks_y: height / BLOCK_SIZE,
+        }
+    }
+
+    /// Every block, least significant planes first.
+    pub fn positions(&self) -> impl Iterator<Item = BlockPosition> + '_ {
+        (0..BIT_PLANES).flat_map(move |plane| {
+            (0..3).flat_map(move |channel| {
+                (0..self.blocks_y).flat_map(move |y| {
+                    (0..self.blocks_x).map(move |x| BlockPosition {
+                        plane,
+                        channel,
+                        x,
+                        y,
+                    })
+                })
+            })
+        })
+    }
+
+    fn sample_index(&self, position: BlockPosition, bit: usize) -> usize {
+        let row = position.y * BLOCK_SIZE + bit / BLOCK_SIZE;
+        let column = position.x * BLOCK_SIZE + bit % BLOCK_SIZE;
+        (row * self.width + column) * 3 + position.channel
+    }
+
+    pub fn block(&self, position: BlockPosition) -> u64 {
+        (0..64).fold(0, |block, bit| {
+            let value = self.gray[self.sample_index(position, bit)];
+            block | ((((value >> position.plane) & 1) as u64) << bit)
+        })
+    }
+
+    pub fn set_block(&mut self, position: BlockPosition, block: u64) {
+        for bit in 0..64 {
+            let index = self.sample_index(position, bit);
+            let mask = 1 << position.plane;
+            if (block >> bit) & 1 == 1 {
+                self.gray[index] |= mask;
+            } else {
+                self.gray[index] &= !mask;
+            }
+        }
+    }
+
+    pub fn write_to(&self, samples: &mut [u8]) {
+        for (sample, &gray) in samples.iter_mut().zip(&self.gray) {
+            *sample = from_gray(gray);
+        }
+    }
+}
+
+/// Bit-plane complexity segmentation: every 8x8 bit-plane block whose
+/// complexity reaches `threshold` looks like noise and is replaced by 63
+/// payload bits. Payload blocks that are too simple are conjugated so the
+/// decoder still recognises them as complex.
+pub fn embed_bpcs_in_image<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut RgbImage,
+    threshold: f64,
+) -> Result<(), Error> {
+    validate_threshold(threshold)?;
+
+    let payload = build_payload(hide_content);
+    let bits = bytes_to_bits(&payload);
+
+    let (width, height) = (carrier.width() as usize, carrier.height() as usize);
+    let steg_image_bytes = carrier.as_flat_samples_mut().samples;
+    let mut planes = BitPlanes::new(steg_image_bytes, width, height);
+
+    let complex_blocks: Vec<BlockPosition> = planes
+        .positions()
+        .filter(|&position| complexity(planes.block(position)) >= threshold)
+        .collect();
+    let needed_blocks = bits.len().div_ceil(DATA_BITS_PER_BLOCK);
+    if needed_blocks > complex_blocks.len() {
+        return Err(Error::Capacity(format!(
+            "Image not large enough to hide {} bytes of data: {} complex blocks at threshold {} hold {} bits, {} bits needed",
+            payload.len() - 5,
+            complex_blocks.len(),
+            threshold,
+            complex_blocks.len() * DATA_BITS_PER_BLOCK,
+            bits.len()
+        )));
+    }
+
+    let mut conjugated = 0;
+    for (chunk, &position) in bits.chunks(DATA_BITS_PER_BLOCK).zip(&complex_blocks) {
+        let mut block = chunk
+            .iter()
+            .enumerate()
+            .fold(0u64, |block, (i, &bit)| block | ((bit as u64) << (i + 1)));
+        if complexity(block) < threshold {
+            block = conjugate(block);
+            conjugated += 1;
+        }
+        planes.set_block(position, block);
+    }
+    planes.write_to(steg_image_bytes);
+
+    println!(
+        "Replaced {} of {} complex blocks ({} conjugated)",
+        needed_blocks,
+        complex_blocks.len(),
+        conjugated
+    );
+
+    Ok(())
+}
+
+/// Hides `hide_content` in the image at `steg_file` with
+/// [`embed_bpcs_in_image`] and saves the result to `output_file`.
+pub fn encode_bpcs<T: Encodable + ?Sized>(
+    hide_content: &T,
+    steg_file: &str,
+    output_file: &str,
+    threshold: f64,
+) -> Result<(), Error> {
+    let mut carrier = load_image(steg_file)?.to_rgb8();
+    embed_bpcs_in_image(hide_content, &mut carrier, threshold)?;
+    save_image(&carrier, output_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::raw::RawContent;
+    use crate::decode::to_image::bpcs::extract_bpcs_from_image;
+    use crate::testing::{noise_image, secret};
+    use crate::traits::ContentType;
+
+    #[test]
+    fn gray_code_is_invertible() {
+        for value in 0..=255u8 {
+            assert_eq!(from_gray(to_gray(value)), value);
+        }
+    }
+
+    #[test]
+    fn conjugation_mirrors_complexity() {
+        for block in [0, u64::MAX, 0x0123_4567_89AB_CDEF, CHECKERBOARD, 0xFF00] {
+            let sum = complexity(block) + complexity(conjugate(block));
+            assert!((sum - 1.0).abs() < 1e-12);
+        }
+        assert_eq!(complexity(0), 0.0);
+        assert_eq!(complexity(CHECKERBOARD), 1.0);
+    }
+
+    #[test]
+    fn round_trip_with_random_and_simple_payloads() {
+        let simple = RawContent::new(vec![0; 500], ContentType::Image);
+        for hidden in [secret(500), simple] {
+            let mut image = noise_image(64, 64);
+            embed_bpcs_in_image(&hidden, &mut image, 0.3).unwrap();
+            let (data, _) = extract_bpcs_from_image(&image, 0.3).unwrap();
+            assert_eq!(data, hidden.data);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_threshold_and_small_image() {
+        let mut image = noise_image(64, 64);
+        assert!(matches!(
+            embed_bpcs_in_image(&secret(10), &mut image, 0.6),
+            Err(Error::InvalidInput(_))
+        ));
+        let mut image = noise_image(8, 8);
+        assert!(matches!(
+            embed_bpcs_in_image(&secret(500), &mut image, 0.3),
+            Err(Error::Capacity(_))
+        ));
+    }
+}
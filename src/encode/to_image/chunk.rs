@@ -0,0 +1,95 @@
+use crate::encode::utils::payload::build_payload;
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::jpeg::{insert_jpeg_app_segments, is_jpeg, STEG_APP_IDENTIFIER};
+use crate::utils::png::{insert_png_chunk, is_png, STEG_CHUNK_ID};
+use std::fs;
+use std::path::Path;
+
+/// Stores the payload in a metadata chunk of a PNG (`stEg` ancillary chunk)
+/// or JPEG (APP15 segments) carrier, given as the bytes of the file. No pixel
+/// data is modified and the output is in the carrier's own format.
+pub fn embed_chunk_in_image<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let payload = build_payload(hide_content);
+
+    if is_png(carrier) {
+        insert_png_chunk(carrier, STEG_CHUNK_ID, &payload)
+    } else if is_jpeg(carrier) {
+        insert_jpeg_app_segments(carrier, STEG_APP_IDENTIFIER, &payload)
+    } else {
+        Err(Error::Format(
+            "Carrier image must be a PNG or JPEG file for chunk embedding.".to_string(),
+        ))
+    }
+}
+
+/// Hides `hide_content` in the image at `steg_file` with
+/// [`embed_chunk_in_image`] and writes the result to `output_file`.
+pub fn encode_chunk<T: Encodable + ?Sized>(
+    hide_content: &T,
+    steg_file: &str,
+    output_file: &str,
+) -> Result<(), Error> {
+    let carrier = fs::read(steg_file)
+        .map_err(|e| Error::io(format!("Failed to read carrier image '{}'", steg_file), e))?;
+
+    let output_ext = Path::new(output_file)
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_lowercase());
+
+    if is_png(&carrier) && output_ext.as_deref() != Some("png") {
+        return Err(Error::InvalidInput(format!(
+            "Output image path '{}' must have a .png extension to match the PNG carrier.",
+            output_file
+        )));
+    }
+    if is_jpeg(&carrier) && !matches!(output_ext.as_deref(), Some("jpg") | Some("jpeg")) {
+        return Err(Error::InvalidInput(format!(
+            "Output image path '{}' must have a .jpg or .jpeg extension to match the JPEG carrier.",
+            output_file
+        )));
+    }
+
+    let output = embed_chunk_in_image(hide_content, &carrier)?;
+    fs::write(output_file, output).map_err(|e| Error::io("Failed to save output image", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_image::chunk::extract_chunk_from_image;
+    use crate::testing::{image_bytes, noise_image, secret};
+    use crate::traits::ContentType;
+    use image::ImageFormat;
+
+    #[test]
+    fn png_and_jpeg_round_trip() {
+        let image = noise_image(32, 32);
+        // Larger than one JPEG segment holds.
+        let hidden = secret(100_000);
+        for format in [ImageFormat::Png, ImageFormat::Jpeg] {
+            let carrier = image_bytes(&image, format);
+            let stego = embed_chunk_in_image(&hidden, &carrier).unwrap();
+            assert!(image::load_from_memory(&stego).is_ok());
+            let (data, content_type) = extract_chunk_from_image(&stego).unwrap().unwrap();
+            assert_eq!(data, hidden.data);
+            assert_eq!(content_type, ContentType::Image);
+        }
+    }
+
+    #[test]
+    fn finds_nothing_in_a_clean_image() {
+        let carrier = image_bytes(&noise_image(8, 8), ImageFormat::Png);
+        assert!(extract_chunk_from_image(&carrier).unwrap().is_none());
+        assert!(matches!(
+            embed_chunk_in_image(&secret(4), b"not an image"),
+            Err(Error::Format(_))
+        ));
+    }
+}
@@ -0,0 +1,49 @@
+use crate::content::raw::RawContent;
+use crate::encode::utils::deniable::{embed_deniable, SLOT_COUNT};
+use crate::encode::utils::payload::build_payload;
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::load::{load_image, save_image};
+use image::RgbImage;
+
+/// Encrypts the content, and optionally a decoy, into separate password
+/// slots spread over every channel value's LSB.
+pub fn embed_deniable_in_image<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut RgbImage,
+    password: &str,
+    decoy: Option<(&RawContent, &str)>,
+) -> Result<(), Error> {
+    let payload = build_payload(hide_content);
+    let decoy_payload = decoy.map(|(content, password)| (build_payload(content), password));
+    let mut payloads = vec![(payload.as_slice(), password)];
+    payloads.extend(
+        decoy_payload
+            .as_ref()
+            .map(|(payload, password)| (payload.as_slice(), *password)),
+    );
+
+    let steg_image_bytes = carrier.as_flat_samples_mut().samples;
+    embed_deniable(steg_image_bytes, &payloads)?;
+    println!(
+        "Filled {} password slots of {} channel values each",
+        SLOT_COUNT,
+        steg_image_bytes.len() / SLOT_COUNT
+    );
+
+    Ok(())
+}
+
+/// Hides `hide_content` in the image at `steg_file` with
+/// [`embed_deniable_in_image`] and saves the result to `output_file`.
+pub fn encode_deniable<T: Encodable + ?Sized>(
+    hide_content: &T,
+    steg_file: &str,
+    output_file: &str,
+    password: &str,
+    decoy: Option<(&RawContent, &str)>,
+) -> Result<(), Error> {
+    let mut carrier = load_image(steg_file)?.to_rgb8();
+    embed_deniable_in_image(hide_content, &mut carrier, password, decoy)?;
+    save_image(&carrier, output_file)
+}
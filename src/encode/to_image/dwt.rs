@@ -0,0 +1,262 @@
+use crate::encode::utils::payload::{build_payload, bytes_to_bits};
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::dsp::{haar_forward_2d, haar_inverse_2d, resize_plane};
+use crate::utils::load::{load_image, save_image};
+use crate::utils::prng::keyed_rng;
+use image::RgbImage;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// The watermark lives in a fixed-size copy of the luminance, so the decoder
+// finds the same coefficients however the image was rescaled.
+const CANONICAL_SIZE: usize = 512;
+const SUBBAND_SIZE: usize = CANONICAL_SIZE / 4;
+
+// Each slot is a block of level-2 HL or LH coefficients whose mean is
+// quantized to hide one bit.
+const BLOCK_SIZE: usize = 2;
+const BLOCKS_PER_ROW: usize = SUBBAND_SIZE / BLOCK_SIZE;
+const QIM_STEP: f64 = 24.0;
+
+pub const HEADER_BITS: usize = (1 + 4) * 8;
+pub const HEADER_REPETITION: usize = 15;
+pub const MIN_DATA_REPETITION: usize = 3;
+
+const EMBEDDING_PASSES: usize = 8;
+const CONVERGED_DELTA: f64 = 0.5;
+
+const LAYOUT_CONTEXT: &str = "dwt-watermark";
+
+/// Level-2 Haar coefficients of the image's luminance, resized to the
+/// canonical size.
+pub fn canonical_coefficients(image: &RgbImage) -> Vec<f64> {
+    let luma: Vec<f64> = image
+        .pixels()
+        .map(|p| 0.299 * p[0] as f64 + 0.587 * p[1] as f64 + 0.114 * p[2] as f64)
+        .collect();
+    let mut plane = resize_plane(
+        &luma,
+        image.width() as usize,
+        image.height() as usize,
+        CANONICAL_SIZE,
+        CANONICAL_SIZE,
+    );
+    haar_forward_2d(&mut plane, CANONICAL_SIZE, CANONICAL_SIZE);
+    haar_forward_2d(&mut plane, CANONICAL_SIZE, CANONICAL_SIZE / 2);
+    plane
+}
+
+/// Keyed order of the coefficient blocks and the dither applied to each.
+pub struct SlotLayout {
+    positions: Vec<(usize, usize)>,
+    dithers: Vec<f64>,
+}
+
+impl SlotLayout {
+    pub fn new(key: &str) -> Self {
+        let mut positions: Vec<(usize, usize)> = (0..BLOCKS_PER_ROW)
+            .flat_map(|by| (0..BLOCKS_PER_ROW).map(move |bx| (bx * BLOCK_SIZE, by * BLOCK_SIZE)))
+            .flat_map(|(x, y)| [(SUBBAND_SIZE + x, y), (x, SUBBAND_SIZE + y)])
+            .collect();
+
+        let mut rng = keyed_rng(LAYOUT_CONTEXT, key);
+        positions.shuffle(&mut rng);
+        let dithers = positions
+            .iter()
+            .map(|_| rng.gen_range(0.0..QIM_STEP))
+            .collect();
+        Self { positions, dithers }
+    }
+
+    pub fn slot_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    fn indices(&self, slot: usize) -> impl Iterator<Item = usize> {
+        let (x, y) = self.positions[slot];
+        (0..BLOCK_SIZE)
+            .flat_map(move |dy| (0..BLOCK_SIZE).map(move |dx| (y + dy) * CANONICAL_SIZE + x + dx))
+    }
+
+    fn mean(&self, plane: &[f64], slot: usize) -> f64 {
+        self.indices(slot).map(|i| plane[i]).sum::<f64>() / (BLOCK_SIZE * BLOCK_SIZE) as f64
+    }
+
+    /// Moves the slot's mean to the nearest point of the bit's lattice.
+    fn embed(&self, plane: &mut [f64], slot: usize, bit: u8) {
+        let offset = self.dithers[slot] + bit as f64 * QIM_STEP / 2.0;
+        let mean = self.mean(plane, slot);
+        let target = QIM_STEP * ((mean - offset) / QIM_STEP).round() + offset;
+        for i in self.indices(slot) {
+            plane[i] += target - mean;
+        }
+    }
+
+    /// Soft decision in [-1, 1]: positive leans towards 0, negative towards 1.
+    pub fn soft_bit(&self, plane: &[f64], slot: usize) -> f64 {
+        let phase = (self.mean(plane, slot) - self.dithers[slot]) / QIM_STEP;
+        (2.0 * std::f64::consts::PI * phase).cos()
+    }
+}
+
+// The transform is linear, so only the change is taken back to the spatial
+// domain and scaled to the carrier's own size.
+fn apply_delta(image: &mut RgbImage, mut delta: Vec<f64>) {
+    haar_inverse_2d(&mut delta, CANONICAL_SIZE, CANONICAL_SIZE / 2);
+    haar_inverse_2d(&mut delta, CANONICAL_SIZE, CANONICAL_SIZE);
+    let delta = resize_plane(
+        &delta,
+        CANONICAL_SIZE,
+        CANONICAL_SIZE,
+        image.width() as usize,
+        image.height() as usize,
+    );
+
+    for (pixel, change) in image.pixels_mut().zip(delta) {
+        for channel in pixel.0.iter_mut() {
+            *channel = (*channel as f64 + change).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// Repetitions given to each data bit once the header slots are taken.
+pub fn data_repetition(slot_count: usize, data_bits: usize) -> usize {
+    (slot_count - HEADER_BITS * HEADER_REPETITION) / data_bits.max(1)
+}
+
+/// Robust watermark: quantization index modulation of block means in the
+/// level-2 HL and LH subbands of the luminance, with keyed block order and
+/// dither. Every bit is repeated over several blocks so the decoder can
+/// outvote errors from JPEG recompression and rescaling.
+pub fn embed_dwt_in_image<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut RgbImage,
+    key: &str,
+) -> Result<(), Error> {
+    let bits = bytes_to_bits(&build_payload(hide_content));
+    let (header_bits, data_bits) = bits.split_at(HEADER_BITS);
+
+    let layout = SlotLayout::new(key);
+    let repetition = data_repetition(layout.slot_count(), data_bits.len());
+    if repetition < MIN_DATA_REPETITION {
+        return Err(Error::Capacity(format!(
+            "Payload too large for a DWT watermark: {} data bits, at most {} fit",
+            data_bits.len(),
+            data_repetition(layout.slot_count(), 1) / MIN_DATA_REPETITION
+        )));
+    }
+
+    println!(
+        "Embedding {} data bits with {}x repetition",
+        data_bits.len(),
+        repetition
+    );
+
+    // Resizing to and from the canonical size blurs the change and rounding
+    // adds noise, so the image is measured again after each pass and the
+    // remaining error is corrected until every block sits on its lattice.
+    let header_slots = HEADER_BITS * HEADER_REPETITION;
+    for _ in 0..EMBEDDING_PASSES {
+        let current = canonical_coefficients(carrier);
+        let mut marked = current.clone();
+        for slot in 0..header_slots {
+            layout.embed(&mut marked, slot, header_bits[slot % HEADER_BITS]);
+        }
+        for offset in 0..data_bits.len() * repetition {
+            layout.embed(
+                &mut marked,
+                header_slots + offset,
+                data_bits[offset % data_bits.len()],
+            );
+        }
+
+        let delta: Vec<f64> = marked.iter().zip(&current).map(|(m, c)| m - c).collect();
+        if delta.iter().all(|d| d.abs() < CONVERGED_DELTA) {
+            break;
+        }
+        apply_delta(carrier, delta);
+    }
+
+    Ok(())
+}
+
+/// Hides `hide_content` in the image at `steg_file` with
+/// [`embed_dwt_in_image`] and saves the result to `output_file`.
+pub fn encode_dwt<T: Encodable + ?Sized>(
+    hide_content: &T,
+    steg_file: &str,
+    output_file: &str,
+    key: &str,
+) -> Result<(), Error> {
+    let mut carrier = load_image(steg_file)?.to_rgb8();
+    embed_dwt_in_image(hide_content, &mut carrier, key)?;
+    save_image(&carrier, output_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_image::dwt::extract_dwt_from_image;
+    use crate::testing::{noise_image, secret};
+    use image::codecs::jpeg::JpegEncoder;
+    use image::imageops::{self, FilterType};
+
+    // Blurred noise, closer to a photo than white noise.
+    fn watermarked() -> RgbImage {
+        let mut image = imageops::blur(&noise_image(300, 300), 1.5);
+        embed_dwt_in_image(&secret(16), &mut image, "key").unwrap();
+        image
+    }
+
+    #[test]
+    fn round_trip() {
+        let watermark = extract_dwt_from_image(&watermarked(), "key").unwrap();
+        assert_eq!(watermark.data, secret(16).data);
+        assert_eq!(watermark.disagreements, 0);
+    }
+
+    #[test]
+    fn survives_jpeg_and_rescaling() {
+        let image = watermarked();
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, 85)
+            .encode_image(&image)
+            .unwrap();
+        let recompressed = image::load_from_memory(&jpeg).unwrap().to_rgb8();
+        let watermark = extract_dwt_from_image(&recompressed, "key").unwrap();
+        assert_eq!(watermark.data, secret(16).data);
+        assert!(watermark.disagreements > 0);
+        assert!(watermark.bit_error_rate() < 0.25);
+
+        let rescaled = imageops::resize(&image, 450, 450, FilterType::Triangle);
+        let watermark = extract_dwt_from_image(&rescaled, "key").unwrap();
+        assert_eq!(watermark.data, secret(16).data);
+    }
+
+    #[test]
+    fn cropping_loses_the_watermark() {
+        let cropped = imageops::crop_imm(&watermarked(), 0, 0, 299, 299).to_image();
+        assert!(matches!(
+            extract_dwt_from_image(&cropped, "key"),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn wrong_key_finds_nothing() {
+        assert!(matches!(
+            extract_dwt_from_image(&watermarked(), "other key"),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_a_large_payload() {
+        let mut image = noise_image(64, 64);
+        assert!(matches!(
+            embed_dwt_in_image(&secret(2000), &mut image, "key"),
+            Err(Error::Capacity(_))
+        ));
+    }
+}
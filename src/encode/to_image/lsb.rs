@@ -1,62 +1,75 @@
+use crate::encode::utils::lsb::LsbEmbedding;
+use crate::error::Error;
+use crate::method::lsb::embed_lsb_in_carrier;
 use crate::traits::Encodable;
-use crate::utils::load::load_image;
-use image::DynamicImage;
+use crate::utils::load::{load_image, save_image};
+use image::RgbImage;
+use rand_chacha::ChaCha20Rng;
 
-pub fn encode_lsb<T: Encodable>(
+/// LSB embedding on the image's RGB channel values; see
+/// [`embed_lsb_in_carrier`].
+pub fn embed_lsb_in_image<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut RgbImage,
+    embedding: LsbEmbedding,
+    fill: Option<ChaCha20Rng>,
+    conceal_key: Option<&str>,
+) -> Result<(), Error> {
+    embed_lsb_in_carrier(carrier, hide_content, embedding, fill, conceal_key)
+}
+
+/// Hides `hide_content` in the image at `steg_file` with
+/// [`embed_lsb_in_image`] and saves the result to `output_file`.
+pub fn encode_lsb<T: Encodable + ?Sized>(
     hide_content: &T,
     steg_file: &str,
     output_file: &str,
-) -> Result<(), String> {
-    let content_type = hide_content.content_type();
-    // let metadata = hide_content.metadata();
-    let hide_data = hide_content.to_bytes();
-
-    let hide_data_len = hide_data.len() as u32;
-    let len_bytes = hide_data_len.to_be_bytes();
-
-    let steg_image = load_image(steg_file);
-    let mut steg_image_rgb = steg_image?.to_rgb8();
-    let steg_image_bytes = steg_image_rgb.as_flat_samples_mut().samples;
-
-    let total_bits_needed = (1 + 4 + hide_data.len()) * 8; // content_type + length + data
-    if total_bits_needed > steg_image_bytes.len() {
-        return Err(format!(
-            "Image not large enough to hide {} bytes of data",
-            hide_data.len()
-        ));
-    }
+    embedding: LsbEmbedding,
+    fill: Option<ChaCha20Rng>,
+    conceal_key: Option<&str>,
+) -> Result<(), Error> {
+    let mut carrier = load_image(steg_file)?.to_rgb8();
+    embed_lsb_in_image(hide_content, &mut carrier, embedding, fill, conceal_key)?;
+    save_image(&carrier, output_file)
+}
 
-    let mut current_index = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_image::lsb::decode_lsb;
+    use crate::testing::{noise_image, secret};
 
-    let content_type_byte = content_type.to_u8();
-    for bit_pos in 0..8 {
-        let bit = (content_type_byte >> (7 - bit_pos)) & 1;
-        steg_image_bytes[current_index] &= 0xFE; // Clear LSB
-        steg_image_bytes[current_index] |= bit; // Set LSB
-        current_index += 1;
-    }
+    #[test]
+    fn path_wrapper_matches_in_memory_embedding() {
+        let dir = tempfile::tempdir().unwrap();
+        let carrier_path = dir.path().join("carrier.png");
+        let output_path = dir.path().join("output.png");
+        let carrier = noise_image(32, 32);
+        save_image(&carrier, carrier_path.to_str().unwrap()).unwrap();
 
-    for &byte in &len_bytes {
-        for bit_pos in 0..8 {
-            let bit = (byte >> (7 - bit_pos)) & 1;
-            steg_image_bytes[current_index] &= 0xFE;
-            steg_image_bytes[current_index] |= bit;
-            current_index += 1;
-        }
-    }
+        let hidden = secret(100);
+        encode_lsb(
+            &hidden,
+            carrier_path.to_str().unwrap(),
+            output_path.to_str().unwrap(),
+            LsbEmbedding::Replacement,
+            None,
+            None,
+        )
+        .unwrap();
 
-    for &byte in &hide_data {
-        for bit_pos in 0..8 {
-            let bit = (byte >> (7 - bit_pos)) & 1;
-            steg_image_bytes[current_index] &= 0xFE;
-            steg_image_bytes[current_index] |= bit;
-            current_index += 1;
-        }
+        let mut expected = carrier;
+        embed_lsb_in_image(
+            &hidden,
+            &mut expected,
+            LsbEmbedding::Replacement,
+            None,
+            None,
+        )
+        .unwrap();
+        let written = load_image(output_path.to_str().unwrap()).unwrap();
+        assert_eq!(written.to_rgb8(), expected);
+        let (data, _) = decode_lsb(&written).unwrap();
+        assert_eq!(data, hidden.data);
     }
-
-    DynamicImage::ImageRgb8(steg_image_rgb)
-        .save(output_file)
-        .map_err(|e| format!("Failed to save output image: {}", e))?;
-
-    Ok(())
 }
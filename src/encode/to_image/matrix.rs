@@ -0,0 +1,46 @@
+use crate::encode::utils::matrix::{choose_k, embed_matrix};
+use crate::encode::utils::payload::{build_payload, bytes_to_bits};
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::load::{load_image, save_image};
+use image::RgbImage;
+
+/// LSB embedding through a (1, 2^k - 1, k) Hamming code, with `k` picked
+/// from the payload to capacity ratio.
+pub fn embed_matrix_in_image<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut RgbImage,
+) -> Result<(), Error> {
+    let payload = build_payload(hide_content);
+    let bits = bytes_to_bits(&payload);
+
+    let steg_image_bytes = carrier.as_flat_samples_mut().samples;
+
+    let k = choose_k(bits.len(), steg_image_bytes.len()).ok_or_else(|| {
+        Error::Capacity(format!(
+            "Image not large enough to hide {} bytes of data",
+            payload.len() - 5
+        ))
+    })?;
+    let changed = embed_matrix(steg_image_bytes, &bits, k);
+    println!(
+        "Matrix embedding with k = {}: {} channel values changed for {} bits",
+        k,
+        changed,
+        bits.len()
+    );
+
+    Ok(())
+}
+
+/// Hides `hide_content` in the image at `steg_file` with
+/// [`embed_matrix_in_image`] and saves the result to `output_file`.
+pub fn encode_matrix<T: Encodable + ?Sized>(
+    hide_content: &T,
+    steg_file: &str,
+    output_file: &str,
+) -> Result<(), Error> {
+    let mut carrier = load_image(steg_file)?.to_rgb8();
+    embed_matrix_in_image(hide_content, &mut carrier)?;
+    save_image(&carrier, output_file)
+}
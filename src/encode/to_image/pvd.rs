@@ -0,0 +1,181 @@
+use crate::encode::utils::payload::{build_payload, bytes_to_bits};
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::load::{load_image, save_image};
+use image::RgbImage;
+
+/// Wu–Tsai range table: (lower bound, bits carried) for each range of
+/// absolute pair differences. Every range is 2^bits wide.
+const RANGES: [(i32, u32); 6] = [(0, 3), (8, 3), (16, 4), (32, 5), (64, 6), (128, 7)];
+
+/// Lower bound and bit count of the range containing `|diff|`.
+pub fn range_for(diff: i32) -> (i32, u32) {
+    *RANGES
+        .iter()
+        .rev()
+        .find(|&&(lower, _)| diff.abs() >= lower)
+        .unwrap()
+}
+
+/// Integer (average, difference) transform of a pair. Changing the
+/// difference and transforming back leaves the average unchanged, so the
+/// decoder sees the same average the encoder tested.
+pub fn to_avg_diff(first: u8, second: u8) -> (i32, i32) {
+    let (first, second) = (first as i32, second as i32);
+    ((first + second).div_euclid(2), first - second)
+}
+
+pub fn from_avg_diff(avg: i32, diff: i32) -> (i32, i32) {
+    let first = avg + (diff + 1).div_euclid(2);
+    (first, first - diff)
+}
+
+/// A pair is usable when every difference in its range, with either sign,
+/// maps back to values inside 0..=255.
+pub fn pair_usable(avg: i32, diff: i32) -> bool {
+    let (lower, bits) = range_for(diff);
+    let upper = lower + (1 << bits) - 1;
+    [upper, -upper].iter().all(|&d| {
+        let (first, second) = from_avg_diff(avg, d);
+        (0..=255).contains(&first) && (0..=255).contains(&second)
+    })
+}
+
+/// Flat sample indices of the pixel pairs used for embedding: horizontally
+/// adjacent pixels in raster order, one pair per colour channel.
+pub fn pair_indices(sample_count: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..sample_count / 6)
+        .flat_map(|pair| (0..3).map(move |channel| (pair * 6 + channel, pair * 6 + 3 + channel)))
+}
+
+/// Pixel-value differencing: each pair hides as many bits as the range of
+/// its difference allows, so busy areas carry more data than smooth ones.
+pub fn embed_pvd_in_image<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut RgbImage,
+) -> Result<(), Error> {
+    let payload = build_payload(hide_content);
+    let bits = bytes_to_bits(&payload);
+
+    let steg_image_bytes = carrier.as_flat_samples_mut().samples;
+
+    let mut bit_index = 0;
+    for (a, b) in pair_indices(steg_image_bytes.len()) {
+        if bit_index >= bits.len() {
+            break;
+        }
+        let (avg, diff) = to_avg_diff(steg_image_bytes[a], steg_image_bytes[b]);
+        if !pair_usable(avg, diff) {
+            continue;
+        }
+
+        let (lower, bit_count) = range_for(diff);
+        let value = (0..bit_count as usize).fold(0, |acc, i| {
+            (acc << 1) | *bits.get(bit_index + i).unwrap_or(&0) as i32
+        });
+        bit_index += bit_count as usize;
+
+        let new_diff = if diff < 0 {
+            -(lower + value)
+        } else {
+            lower + value
+        };
+        let (first, second) = from_avg_diff(avg, new_diff);
+        steg_image_bytes[a] = first as u8;
+        steg_image_bytes[b] = second as u8;
+    }
+
+    if bit_index < bits.len() {
+        return Err(Error::Capacity(format!(
+            "Image not large enough to hide {} bytes of data with pixel-value differencing",
+            payload.len() - 5
+        )));
+    }
+
+    Ok(())
+}
+
+/// Hides `hide_content` in the image at `steg_file` with
+/// [`embed_pvd_in_image`] and saves the result to `output_file`.
+pub fn encode_pvd<T: Encodable + ?Sized>(
+    hide_content: &T,
+    steg_file: &str,
+    output_file: &str,
+) -> Result<(), Error> {
+    let mut carrier = load_image(steg_file)?.to_rgb8();
+    embed_pvd_in_image(hide_content, &mut carrier)?;
+    save_image(&carrier, output_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_image::pvd::extract_pvd_from_image;
+    use crate::testing::{noise_image, secret};
+
+    #[test]
+    fn avg_diff_transform_is_invertible() {
+        for first in 0..=255u8 {
+            for second in 0..=255u8 {
+                let (avg, diff) = to_avg_diff(first, second);
+                assert_eq!(from_avg_diff(avg, diff), (first as i32, second as i32));
+            }
+        }
+    }
+
+    #[test]
+    fn usable_pairs_stay_usable_within_their_range() {
+        for first in 0..=255u8 {
+            for second in 0..=255u8 {
+                let (avg, diff) = to_avg_diff(first, second);
+                if !pair_usable(avg, diff) {
+                    continue;
+                }
+                let (lower, bits) = range_for(diff);
+                for value in 0..1 << bits {
+                    let new_diff = if diff < 0 {
+                        -(lower + value)
+                    } else {
+                        lower + value
+                    };
+                    assert_eq!(range_for(new_diff), (lower, bits));
+                    assert!(pair_usable(avg, new_diff));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let hidden = secret(1000);
+        let mut image = noise_image(64, 64);
+        embed_pvd_in_image(&hidden, &mut image).unwrap();
+        let (data, _) = extract_pvd_from_image(&image).unwrap();
+        assert_eq!(data, hidden.data);
+    }
+
+    #[test]
+    fn skips_pairs_near_the_value_limits() {
+        let noise = noise_image(64, 64);
+        let mut image = RgbImage::from_fn(64, 64, |x, y| {
+            if (x / 2 + y) % 3 == 0 {
+                image::Rgb([255, 0, 255])
+            } else {
+                *noise.get_pixel(x, y)
+            }
+        });
+        let hidden = secret(300);
+        embed_pvd_in_image(&hidden, &mut image).unwrap();
+        let (data, _) = extract_pvd_from_image(&image).unwrap();
+        assert_eq!(data, hidden.data);
+    }
+
+    #[test]
+    fn rejects_a_small_image() {
+        let mut image = noise_image(8, 8);
+        assert!(matches!(
+            embed_pvd_in_image(&secret(1000), &mut image),
+            Err(Error::Capacity(_))
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::encode::utils::cost::hill_costs;
+use crate::encode::utils::lsb::{embed_lsb, LsbEmbedding, LsbSample};
+use crate::encode::utils::payload::{build_payload, bytes_to_bits};
+use crate::encode::utils::stc::stc_embed;
+use crate::error::Error;
+use crate::traits::Encodable;
+use crate::utils::load::{load_image, save_image};
+use image::RgbImage;
+
+/// Samples holding the message length in bits as a plain-LSB u32.
+pub const PREAMBLE_SAMPLES: usize = 32;
+
+/// Minimal-distortion embedding: syndrome-trellis codes choose which LSBs
+/// to change so that the total HILL cost is as low as possible, which keeps
+/// the changes in textured regions. Changed values are moved by +/-1.
+pub fn embed_stc_in_image<T: Encodable + ?Sized>(
+    hide_content: &T,
+    carrier: &mut RgbImage,
+    mut embedding: LsbEmbedding,
+) -> Result<(), Error> {
+    let payload = build_payload(hide_content);
+    let bits = bytes_to_bits(&payload);
+
+    let costs = hill_costs(carrier);
+    let steg_image_bytes = carrier.as_flat_samples_mut().samples;
+
+    if steg_image_bytes.len() < PREAMBLE_SAMPLES + bits.len() {
+        return Err(Error::Capacity(format!(
+            "Image not large enough to hide {} bytes of data",
+            payload.len() - 5
+        )));
+    }
+
+    let (preamble, cover) = steg_image_bytes.split_at_mut(PREAMBLE_SAMPLES);
+    let cover_bits: Vec<u8> = cover.iter().map(|v| v.lsb()).collect();
+    let stego_bits = stc_embed(&cover_bits, &costs[PREAMBLE_SAMPLES..], &bits)?;
+
+    embed_lsb(
+        preamble,
+        &bytes_to_bits(&(bits.len() as u32).to_be_bytes()),
+        &mut embedding,
+    );
+    let changed = embed_lsb(cover, &stego_bits, &mut embedding);
+    println!(
+        "Syndrome-trellis coding changed {} channel values for {} bits",
+        changed,
+        bits.len()
+    );
+
+    Ok(())
+}
+
+/// Hides `hide_content` in the image at `steg_file` with
+/// [`embed_stc_in_image`] and saves the result to `output_file`.
+pub fn encode_stc<T: Encodable + ?Sized>(
+    hide_content: &T,
+    steg_file: &str,
+    output_file: &str,
+    embedding: LsbEmbedding,
+) -> Result<(), Error> {
+    let mut carrier = load_image(steg_file)?.to_rgb8();
+    embed_stc_in_image(hide_content, &mut carrier, embedding)?;
+    save_image(&carrier, output_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_image::stc::extract_stc_from_image;
+    use crate::testing::{noise_image, secret};
+
+    #[test]
+    fn round_trip_moves_values_by_one() {
+        let hidden = secret(150);
+        let original = noise_image(48, 48);
+        let mut image = original.clone();
+        embed_stc_in_image(&hidden, &mut image, LsbEmbedding::matching(Some("key"))).unwrap();
+
+        assert!(original
+            .as_raw()
+            .iter()
+            .zip(image.as_raw())
+            .all(|(&a, &b)| a.abs_diff(b) <= 1));
+        let (data, _) = extract_stc_from_image(&image).unwrap();
+        assert_eq!(data, hidden.data);
+    }
+
+    #[test]
+    fn round_trip_on_a_large_image() {
+        // Over half a million trellis stages, traced back in nine segments.
+        let hidden = secret(5_000);
+        let mut image = noise_image(500, 400);
+        embed_stc_in_image(&hidden, &mut image, LsbEmbedding::Replacement).unwrap();
+        let (data, _) = extract_stc_from_image(&image).unwrap();
+        assert_eq!(data, hidden.data);
+    }
+
+    #[test]
+    fn rejects_a_small_image() {
+        let mut image = noise_image(8, 8);
+        assert!(matches!(
+            embed_stc_in_image(&secret(150), &mut image, LsbEmbedding::matching(None)),
+            Err(Error::Capacity(_))
+        ));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::encode::utils::lsb::LsbSample;
+use crate::encode::utils::payload::bytes_to_bits;
+use crate::error::Error;
+use crate::traits::{ContentType, Encodable};
+use crate::utils::prng::keyed_rng;
+use rand::Rng;
+use rand_chacha::ChaCha20Rng;
+
+const CONCEAL_CONTEXT: &str = "concealed-header";
+
+/// Content type, data length (u32, big-endian) and flags.
+pub const CONCEALED_HEADER_BITS: usize = (1 + 4 + 1) * 8;
+
+/// The LSBs after the payload hold keyed noise rather than carrier bits.
+pub const FLAG_FILLED: u8 = 1;
+const KNOWN_FLAGS: u8 = FLAG_FILLED;
+
+/// A payload whose bits are whitened with a key-derived stream and whose
+/// first bit goes at a key-derived sample, wrapping around the carrier.
+pub struct ConcealedPayload {
+    pub offset: usize,
+    pub bits: Vec<u8>,
+}
+
+/// Starts the keyed stream: the start sample is drawn first, then one
+/// whitening bit per payload bit.
+fn conceal_stream(key: &str, sample_count: usize) -> (usize, ChaCha20Rng) {
+    let mut rng = keyed_rng(CONCEAL_CONTEXT, key);
+    let offset = rng.gen_range(0..sample_count.max(1));
+    (offset, rng)
+}
+
+pub fn conceal_payload<T: Encodable + ?Sized>(
+    hide_content: &T,
+    key: &str,
+    sample_count: usize,
+    flags: u8,
+) -> ConcealedPayload {
+    let data = hide_content.to_bytes();
+    let mut payload = Vec::with_capacity(1 + 4 + 1 + data.len());
+    payload.push(hide_content.content_type().to_u8());
+    payload.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    payload.push(flags);
+    payload.extend_from_slice(&data);
+
+    let (offset, mut rng) = conceal_stream(key, sample_count);
+    let bits = bytes_to_bits(&payload)
+        .into_iter()
+        .map(|bit| bit ^ rng.gen_bool(0.5) as u8)
+        .collect();
+    ConcealedPayload { offset, bits }
+}
+
+/// Reads a payload written by `conceal_payload` from the LSBs of `samples`.
+/// A wrong key yields a header that fails validation.
+pub fn extract_concealed<S: LsbSample>(
+    samples: &[S],
+    key: &str,
+) -> Result<(Vec<u8>, ContentType), Error> {
+    let not_found = || Error::NotFound("No concealed payload found with this key".to_string());
+
+    let (offset, mut rng) = conceal_stream(key, samples.len());
+    let mut bits = samples[offset..]
+        .iter()
+        .chain(&samples[..offset])
+        .map(|sample| sample.lsb() ^ rng.gen_bool(0.5) as u8);
+    let mut read_bytes = |count: usize| -> Vec<u8> {
+        (0..count)
+            .map_while(|_| (0..8).try_fold(0u8, |byte, _| bits.next().map(|bit| (byte << 1) | bit)))
+            .collect()
+    };
+
+    let header = read_bytes(CONCEALED_HEADER_BITS / 8);
+    if header.len() < CONCEALED_HEADER_BITS / 8 || header[5] & !KNOWN_FLAGS != 0 {
+        return Err(not_found());
+    }
+    let content_type = ContentType::from_u8(header[0]).ok_or_else(not_found)?;
+    let data_len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if CONCEALED_HEADER_BITS + data_len.saturating_mul(8) > samples.len() {
+        return Err(not_found());
+    }
+
+    Ok((read_bytes(data_len), content_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode::utils::lsb::{fill_rng, LsbEmbedding};
+    use crate::method::lsb::{embed_lsb_in_carrier, extract_lsb_from_carrier};
+    use crate::testing::{noise_image, secret};
+
+    #[test]
+    fn round_trip_wraps_around_the_carrier() {
+        let hidden = secret(100);
+        let mut image = noise_image(20, 16);
+        let samples = image.as_raw().len();
+        let concealed = conceal_payload(&hidden, "key", samples, 0);
+        assert!(concealed.offset + concealed.bits.len() > samples);
+
+        embed_lsb_in_carrier(
+            &mut image,
+            &hidden,
+            LsbEmbedding::Replacement,
+            None,
+            Some("key"),
+        )
+        .unwrap();
+        let (data, _) = extract_concealed(image.as_raw(), "key").unwrap();
+        assert_eq!(data, hidden.data);
+    }
+
+    #[test]
+    fn needs_the_key() {
+        let hidden = secret(100);
+        let mut image = noise_image(32, 32);
+        let fill = Some(fill_rng(Some("key")));
+        embed_lsb_in_carrier(
+            &mut image,
+            &hidden,
+            LsbEmbedding::Replacement,
+            fill,
+            Some("key"),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            extract_concealed(image.as_raw(), "other key"),
+            Err(Error::NotFound(_))
+        ));
+        assert!(!matches!(
+            extract_lsb_from_carrier(&image, None),
+            Ok((data, _)) if data == hidden.data
+        ));
+        let (data, _) = extract_lsb_from_carrier(&image, Some("key")).unwrap();
+        assert_eq!(data, hidden.data);
+    }
+
+    #[test]
+    fn records_the_fill_flag() {
+        let hidden = secret(4);
+        let concealed = conceal_payload(&hidden, "key", 1000, FLAG_FILLED);
+        let (offset, mut rng) = conceal_stream("key", 1000);
+        assert_eq!(offset, concealed.offset);
+        let plain: Vec<u8> = concealed
+            .bits
+            .iter()
+            .map(|bit| bit ^ rng.gen_bool(0.5) as u8)
+            .collect();
+        assert_eq!(&plain[40..48], &[0, 0, 0, 0, 0, 0, 0, 1]);
+    }
+}
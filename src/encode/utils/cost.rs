@@ -0,0 +1,91 @@
+use image::RgbImage;
+
+// Kernel of the high-pass (KB) filter used by HILL.
+const HIGH_PASS: [[f64; 3]; 3] = [[-1.0, 2.0, -1.0], [2.0, -4.0, 2.0], [-1.0, 2.0, -1.0]];
+const FIRST_BOX_RADIUS: usize = 1;
+const SECOND_BOX_RADIUS: usize = 7;
+
+/// HILL distortion costs (Li et al., 2014) for changing each value of an RGB
+/// image by one, in the order of its flat sample buffer. Each colour channel
+/// is treated as a separate grayscale plane. Costs are low in textured areas
+/// and high in smooth ones.
+pub fn hill_costs(image: &RgbImage) -> Vec<f64> {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let samples = image.as_flat_samples().samples;
+
+    let mut costs = vec![0.0; samples.len()];
+    for channel in 0..3 {
+        let plane: Vec<f64> = samples
+            .iter()
+            .skip(channel)
+            .step_by(3)
+            .map(|&v| v as f64)
+            .collect();
+
+        let residual: Vec<f64> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let mut sum = 0.0;
+                for (dy, row) in HIGH_PASS.iter().enumerate() {
+                    for (dx, &k) in row.iter().enumerate() {
+                        let sx = mirror(x as isize + dx as isize - 1, width);
+                        let sy = mirror(y as isize + dy as isize - 1, height);
+                        sum += k * plane[sy * width + sx];
+                    }
+                }
+                sum.abs()
+            })
+            .collect();
+
+        let inverse: Vec<f64> = box_filter(&residual, width, height, FIRST_BOX_RADIUS)
+            .into_iter()
+            .map(|r| 1.0 / (r + 1e-10))
+            .collect();
+        let plane_costs = box_filter(&inverse, width, height, SECOND_BOX_RADIUS);
+
+        for (i, cost) in plane_costs.into_iter().enumerate() {
+            costs[i * 3 + channel] = cost;
+        }
+    }
+    costs
+}
+
+// Reflects out-of-range coordinates back into [0, len).
+fn mirror(i: isize, len: usize) -> usize {
+    let len = len as isize;
+    let reflected = if i < 0 {
+        -i - 1
+    } else if i >= len {
+        2 * len - i - 1
+    } else {
+        i
+    };
+    reflected.clamp(0, len - 1) as usize
+}
+
+// Mean over a (2r + 1) x (2r + 1) window, done as two 1-D passes.
+fn box_filter(plane: &[f64], width: usize, height: usize, radius: usize) -> Vec<f64> {
+    let size = (2 * radius + 1) as f64;
+    let r = radius as isize;
+
+    let mut horizontal = vec![0.0; plane.len()];
+    for y in 0..height {
+        for x in 0..width {
+            horizontal[y * width + x] = (-r..=r)
+                .map(|d| plane[y * width + mirror(x as isize + d, width)])
+                .sum::<f64>()
+                / size;
+        }
+    }
+
+    let mut out = vec![0.0; plane.len()];
+    for y in 0..height {
+        for x in 0..width {
+            out[y * width + x] = (-r..=r)
+                .map(|d| horizontal[mirror(y as isize + d, height) * width + x])
+                .sum::<f64>()
+                / size;
+        }
+    }
+    out
+}
@@ -0,0 +1,205 @@
+use crate::encode::utils::lsb::LsbSample;
+use crate::encode::utils::payload::bytes_to_bits;
+use crate::error::Error;
+use crate::utils::password::{derive_master_key, derive_subkey, SALT_LEN};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+/// Independent password slots per carrier. Sample `i` belongs to slot
+/// `i % SLOT_COUNT`.
+pub const SLOT_COUNT: usize = 2;
+
+const SALT_BITS: usize = SALT_LEN * 8;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = 4 + TAG_LEN;
+const HEADER_NONCE: [u8; 12] = [0; 12];
+const BODY_NONCE: [u8; 12] = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+fn slot_indices(sample_count: usize, slot: usize) -> Vec<usize> {
+    (slot..sample_count).step_by(SLOT_COUNT).collect()
+}
+
+/// Derives a slot's cipher and the keyed order of its samples after the
+/// salt. The salt itself sits in the slot's first samples, unpermuted.
+fn unlock_slot(
+    indices: &[usize],
+    salt: &[u8; SALT_LEN],
+    password: &str,
+) -> Result<(ChaCha20Poly1305, Vec<usize>), Error> {
+    let master_key = derive_master_key(password, salt)?;
+    let mut order = indices[SALT_BITS..].to_vec();
+    order.shuffle(&mut ChaCha20Rng::from_seed(derive_subkey(
+        &master_key,
+        "deniable-permutation",
+    )));
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&derive_subkey(
+        &master_key,
+        "deniable-encryption",
+    )));
+    Ok((cipher, order))
+}
+
+fn read_bytes<S: LsbSample>(samples: &[S], indices: &[usize]) -> Vec<u8> {
+    indices
+        .chunks_exact(8)
+        .map(|byte| {
+            byte.iter()
+                .fold(0u8, |acc, &i| (acc << 1) | samples[i].lsb())
+        })
+        .collect()
+}
+
+/// Largest payload, in bytes, that fits in every slot of `sample_count`
+/// samples.
+pub fn slot_capacity(sample_count: usize) -> usize {
+    let slot_bytes = (sample_count / SLOT_COUNT).saturating_sub(SALT_BITS) / 8;
+    slot_bytes.saturating_sub(HEADER_LEN + TAG_LEN)
+}
+
+/// Encrypts each `(payload, password)` pair into its own slot, in random
+/// slot order, after overwriting every LSB with random bits. Unused slots and
+/// the tail of used ones are indistinguishable from ciphertext, so a carrier
+/// gives no hint of how many payloads it holds.
+pub fn embed_deniable<S: LsbSample>(
+    samples: &mut [S],
+    payloads: &[(&[u8], &str)],
+) -> Result<(), Error> {
+    if payloads.len() > SLOT_COUNT {
+        return Err(Error::InvalidInput(format!(
+            "At most {} payloads can share a carrier",
+            SLOT_COUNT
+        )));
+    }
+    let capacity = slot_capacity(samples.len());
+    if let Some((payload, _)) = payloads
+        .iter()
+        .find(|(payload, _)| payload.len() > capacity)
+    {
+        return Err(Error::Capacity(format!(
+            "Carrier not large enough: a payload of {} bytes exceeds the {} bytes available per password",
+            payload.len(),
+            capacity
+        )));
+    }
+
+    let mut rng = ChaCha20Rng::from_entropy();
+    for sample in samples.iter_mut() {
+        *sample = sample.with_lsb(rng.gen_bool(0.5) as u8);
+    }
+
+    let mut slots: Vec<usize> = (0..SLOT_COUNT).collect();
+    slots.shuffle(&mut rng);
+    for (&(payload, password), &slot) in payloads.iter().zip(&slots) {
+        let indices = slot_indices(samples.len(), slot);
+        let mut salt = [0u8; SALT_LEN];
+        rng.fill_bytes(&mut salt);
+        for (&i, bit) in indices.iter().zip(bytes_to_bits(&salt)) {
+            samples[i] = samples[i].with_lsb(bit);
+        }
+
+        let (cipher, order) = unlock_slot(&indices, &salt, password)?;
+        let mut sealed = cipher
+            .encrypt(
+                Nonce::from_slice(&HEADER_NONCE),
+                (payload.len() as u32).to_be_bytes().as_slice(),
+            )
+            .map_err(|_| Error::Crypto("Failed to encrypt the payload header".to_string()))?;
+        sealed.extend(
+            cipher
+                .encrypt(Nonce::from_slice(&BODY_NONCE), payload)
+                .map_err(|_| Error::Crypto("Failed to encrypt the payload".to_string()))?,
+        );
+        for (&i, bit) in order.iter().zip(bytes_to_bits(&sealed)) {
+            samples[i] = samples[i].with_lsb(bit);
+        }
+    }
+    Ok(())
+}
+
+/// Tries `password` against every slot and returns the payload of the one
+/// it unlocks.
+pub fn extract_deniable<S: LsbSample>(samples: &[S], password: &str) -> Result<Vec<u8>, Error> {
+    for slot in 0..SLOT_COUNT {
+        let indices = slot_indices(samples.len(), slot);
+        if indices.len() < SALT_BITS + HEADER_LEN * 8 {
+            continue;
+        }
+        let mut salt = [0u8; SALT_LEN];
+        salt.copy_from_slice(&read_bytes(samples, &indices[..SALT_BITS]));
+
+        let (cipher, order) = unlock_slot(&indices, &salt, password)?;
+        let header = read_bytes(samples, &order[..HEADER_LEN * 8]);
+        let Ok(len_bytes) = cipher.decrypt(Nonce::from_slice(&HEADER_NONCE), header.as_slice())
+        else {
+            continue;
+        };
+        let mut payload_len = [0u8; 4];
+        payload_len.copy_from_slice(&len_bytes);
+        let body_len = u32::from_be_bytes(payload_len) as usize + TAG_LEN;
+        let Some(body_indices) = order.get(HEADER_LEN * 8..(HEADER_LEN + body_len) * 8) else {
+            continue;
+        };
+        let body = read_bytes(samples, body_indices);
+        if let Ok(payload) = cipher.decrypt(Nonce::from_slice(&BODY_NONCE), body.as_slice()) {
+            return Ok(payload);
+        }
+    }
+    Err(Error::NotFound(
+        "No hidden data could be unlocked with this password".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_audio::deniable::extract_deniable_from_audio;
+    use crate::decode::to_image::deniable::extract_deniable_from_image;
+    use crate::encode::to_audio::deniable::embed_deniable_in_audio;
+    use crate::encode::to_image::deniable::embed_deniable_in_image;
+    use crate::testing::{noise_audio, noise_image, secret};
+
+    #[test]
+    fn each_password_reveals_its_own_payload() {
+        let hidden = secret(200);
+        let decoy = secret(30);
+        let mut image = noise_image(48, 48);
+        embed_deniable_in_image(&hidden, &mut image, "real", Some((&decoy, "decoy"))).unwrap();
+
+        let (data, _) = extract_deniable_from_image(&image, "real").unwrap();
+        assert_eq!(data, hidden.data);
+        let (data, _) = extract_deniable_from_image(&image, "decoy").unwrap();
+        assert_eq!(data, decoy.data);
+        assert!(matches!(
+            extract_deniable_from_image(&image, "wrong"),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn audio_round_trip() {
+        let hidden = secret(100);
+        let mut audio = noise_audio(2000);
+        embed_deniable_in_audio(&hidden, &mut audio, "real", None).unwrap();
+        let (data, _) = extract_deniable_from_audio(&audio, "real").unwrap();
+        assert_eq!(data, hidden.data);
+    }
+
+    #[test]
+    fn rejects_too_many_or_too_large_payloads() {
+        let mut samples = vec![0u8; 4000];
+        let payloads: Vec<(&[u8], &str)> = vec![(b"a", "1"), (b"b", "2"), (b"c", "3")];
+        assert!(matches!(
+            embed_deniable(&mut samples, &payloads),
+            Err(Error::InvalidInput(_))
+        ));
+
+        let large = vec![0u8; slot_capacity(samples.len()) + 1];
+        assert!(matches!(
+            embed_deniable(&mut samples, &[(&large, "1")]),
+            Err(Error::Capacity(_))
+        ));
+    }
+}
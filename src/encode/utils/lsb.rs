@@ -0,0 +1,176 @@
+use crate::utils::prng::keyed_rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+const MATCHING_CONTEXT: &str = "lsb-matching";
+const FILL_CONTEXT: &str = "lsb-fill";
+
+/// How a sample whose LSB differs from the bit to hide is changed.
+pub enum LsbEmbedding {
+    /// Overwrite the LSB (`&= !1; |= bit`).
+    Replacement,
+    /// Add or subtract one at random, which avoids the pairs-of-values
+    /// artefact that chi-square and RS analysis detect.
+    Matching(Box<ChaCha20Rng>),
+}
+
+impl LsbEmbedding {
+    /// LSB matching driven by `key`, or by a random seed when no key is given.
+    pub fn matching(key: Option<&str>) -> Self {
+        LsbEmbedding::Matching(Box::new(match key {
+            Some(key) => keyed_rng(MATCHING_CONTEXT, key),
+            None => ChaCha20Rng::from_entropy(),
+        }))
+    }
+}
+
+/// Noise source for the LSBs after the payload, driven by `key` or by a
+/// random seed when no key is given.
+pub fn fill_rng(key: Option<&str>) -> ChaCha20Rng {
+    match key {
+        Some(key) => keyed_rng(FILL_CONTEXT, key),
+        None => ChaCha20Rng::from_entropy(),
+    }
+}
+
+/// Carrier sample types that can hold a bit in their LSB.
+pub trait LsbSample: Copy {
+    fn lsb(self) -> u8;
+    fn with_lsb(self, bit: u8) -> Self;
+    /// Moves the value one step up or down, stepping the other way at the
+    /// type's bounds so the result never wraps.
+    fn step(self, up: bool) -> Self;
+}
+
+impl LsbSample for u8 {
+    fn lsb(self) -> u8 {
+        self & 1
+    }
+
+    fn with_lsb(self, bit: u8) -> Self {
+        (self & 0xFE) | bit
+    }
+
+    fn step(self, up: bool) -> Self {
+        match (self, up) {
+            (u8::MAX, _) => self - 1,
+            (u8::MIN, _) => self + 1,
+            (_, true) => self + 1,
+            (_, false) => self - 1,
+        }
+    }
+}
+
+impl LsbSample for i16 {
+    fn lsb(self) -> u8 {
+        (self & 1) as u8
+    }
+
+    fn with_lsb(self, bit: u8) -> Self {
+        (self & !1) | bit as i16
+    }
+
+    fn step(self, up: bool) -> Self {
+        match (self, up) {
+            (i16::MAX, _) => self - 1,
+            (i16::MIN, _) => self + 1,
+            (_, true) => self + 1,
+            (_, false) => self - 1,
+        }
+    }
+}
+
+/// Hides `bits` in the LSBs of the leading samples. Returns the number of
+/// samples that had to change.
+pub fn embed_lsb<S: LsbSample>(
+    samples: &mut [S],
+    bits: &[u8],
+    embedding: &mut LsbEmbedding,
+) -> usize {
+    let mut changed = 0;
+    for (sample, &bit) in samples.iter_mut().zip(bits) {
+        if sample.lsb() == bit {
+            continue;
+        }
+        *sample = match embedding {
+            LsbEmbedding::Replacement => sample.with_lsb(bit),
+            LsbEmbedding::Matching(rng) => sample.step(rng.gen_bool(0.5)),
+        };
+        changed += 1;
+    }
+    changed
+}
+
+/// Overwrites the LSBs of the samples from `start` on with `bits`, wrapping
+/// around to the first sample.
+pub fn set_lsbs_at<S: LsbSample>(samples: &mut [S], start: usize, bits: &[u8]) {
+    let len = samples.len();
+    for (i, &bit) in bits.iter().enumerate() {
+        let sample = &mut samples[(start + i) % len];
+        *sample = sample.with_lsb(bit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_image::lsb::decode_lsb;
+    use crate::encode::to_image::lsb::embed_lsb_in_image;
+    use crate::testing::{noise_image, secret};
+    use image::DynamicImage;
+
+    #[test]
+    fn step_never_wraps() {
+        assert_eq!(u8::MAX.step(true), u8::MAX - 1);
+        assert_eq!(u8::MIN.step(false), 1);
+        assert_eq!(i16::MAX.step(true), i16::MAX - 1);
+        assert_eq!(i16::MIN.step(false), i16::MIN + 1);
+        assert_eq!(7u8.step(true), 8);
+        assert_eq!((-7i16).step(false), -8);
+    }
+
+    #[test]
+    fn matching_changes_samples_by_one() {
+        let original: Vec<u8> = (0..=255).chain(0..=255).collect();
+        let bits: Vec<u8> = (0..original.len()).map(|i| (i % 3 == 0) as u8).collect();
+        let mut samples = original.clone();
+        embed_lsb(
+            &mut samples,
+            &bits,
+            &mut LsbEmbedding::matching(Some("key")),
+        );
+
+        for ((&before, &after), &bit) in original.iter().zip(&samples).zip(&bits) {
+            assert_eq!(after.lsb(), bit);
+            assert!(before.abs_diff(after) <= 1);
+        }
+    }
+
+    #[test]
+    fn matching_is_keyed() {
+        let bits = vec![1; 64];
+        let embed_with = |key| {
+            let mut samples = vec![100u8; 64];
+            embed_lsb(&mut samples, &bits, &mut LsbEmbedding::matching(Some(key)));
+            samples
+        };
+        assert_eq!(embed_with("a"), embed_with("a"));
+        assert_ne!(embed_with("a"), embed_with("b"));
+    }
+
+    #[test]
+    fn matching_round_trip() {
+        let hidden = secret(300);
+        let mut image = noise_image(40, 40);
+        embed_lsb_in_image(
+            &hidden,
+            &mut image,
+            LsbEmbedding::matching(None),
+            None,
+            None,
+        )
+        .unwrap();
+        let (data, _) = decode_lsb(&DynamicImage::ImageRgb8(image)).unwrap();
+        assert_eq!(data, hidden.data);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::encode::utils::lsb::LsbSample;
+use crate::error::Error;
+
+/// Samples holding the code parameter `k` as 8 plain LSBs, ahead of the
+/// matrix-coded payload.
+pub const K_HEADER_SAMPLES: usize = 8;
+pub const MAX_K: u8 = 16;
+
+fn block_len(k: u8) -> usize {
+    (1usize << k) - 1
+}
+
+/// Largest `k` whose (1, 2^k - 1, k) Hamming code fits `bit_count` bits in
+/// `sample_count` samples. Larger codes change fewer samples per hidden bit.
+pub fn choose_k(bit_count: usize, sample_count: usize) -> Option<u8> {
+    let available = sample_count.checked_sub(K_HEADER_SAMPLES)?;
+    (1..=MAX_K)
+        .rev()
+        .find(|&k| bit_count.div_ceil(k as usize) * block_len(k) <= available)
+}
+
+/// XOR of the (1-based) positions of the block's samples whose LSB is set.
+fn syndrome<S: LsbSample>(block: &[S]) -> usize {
+    block
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.lsb() == 1)
+        .fold(0, |acc, (i, _)| acc ^ (i + 1))
+}
+
+/// Writes `k` and then `bits`, `k` at a time, so that each block of
+/// 2^k - 1 samples has the group as its syndrome. At most one sample per
+/// block changes. Returns the number of changed samples.
+pub fn embed_matrix<S: LsbSample>(samples: &mut [S], bits: &[u8], k: u8) -> usize {
+    let (k_samples, cover) = samples.split_at_mut(K_HEADER_SAMPLES);
+    let mut changed = 0;
+    for (i, sample) in k_samples.iter_mut().enumerate() {
+        let bit = (k >> (7 - i)) & 1;
+        if sample.lsb() != bit {
+            *sample = sample.with_lsb(bit);
+            changed += 1;
+        }
+    }
+
+    for (group, block) in bits
+        .chunks(k as usize)
+        .zip(cover.chunks_exact_mut(block_len(k)))
+    {
+        let message = (0..k as usize).fold(0, |acc, i| {
+            (acc << 1) | *group.get(i).unwrap_or(&0) as usize
+        });
+        let flip = syndrome(block) ^ message;
+        if flip != 0 {
+            let sample = &mut block[flip - 1];
+            *sample = sample.with_lsb(sample.lsb() ^ 1);
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Reads `k` and every matrix-coded bit that fits in `samples`.
+pub fn extract_matrix<S: LsbSample>(samples: &[S]) -> Result<Vec<u8>, Error> {
+    if samples.len() < K_HEADER_SAMPLES {
+        return Err(Error::NotFound(
+            "Carrier too small to contain matrix embedding parameters".to_string(),
+        ));
+    }
+    let k = samples[..K_HEADER_SAMPLES]
+        .iter()
+        .fold(0u8, |acc, s| (acc << 1) | s.lsb());
+    if !(1..=MAX_K).contains(&k) {
+        return Err(Error::Corrupt(format!(
+            "Corrupted data: invalid matrix embedding parameter k = {}",
+            k
+        )));
+    }
+    println!("Detected (1, {}, {}) Hamming code", block_len(k), k);
+
+    Ok(samples[K_HEADER_SAMPLES..]
+        .chunks_exact(block_len(k))
+        .flat_map(|block| {
+            let message = syndrome(block);
+            (0..k).rev().map(move |i| ((message >> i) & 1) as u8)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::to_audio::matrix::extract_matrix_from_audio;
+    use crate::decode::to_image::matrix::extract_matrix_from_image;
+    use crate::encode::to_audio::matrix::embed_matrix_in_audio;
+    use crate::encode::to_image::matrix::embed_matrix_in_image;
+    use crate::testing::{noise_audio, noise_image, secret};
+
+    #[test]
+    fn changes_at_most_one_sample_per_block() {
+        let bits: Vec<u8> = (0..60).map(|i| (i * 7 % 5 % 2) as u8).collect();
+        for k in [1, 3, 6] {
+            let original: Vec<u8> = (0..=255).cycle().take(2000).collect();
+            let mut samples = original.clone();
+            embed_matrix(&mut samples, &bits, k);
+
+            for (before, after) in original[K_HEADER_SAMPLES..]
+                .chunks(block_len(k))
+                .zip(samples[K_HEADER_SAMPLES..].chunks(block_len(k)))
+            {
+                let changed = before.iter().zip(after).filter(|(b, a)| b != a).count();
+                assert!(changed <= 1);
+            }
+            assert_eq!(extract_matrix(&samples).unwrap()[..bits.len()], bits[..]);
+        }
+    }
+
+    #[test]
+    fn chooses_the_largest_code_that_fits() {
+        assert_eq!(choose_k(100, K_HEADER_SAMPLES + 100), Some(1));
+        assert_eq!(choose_k(100, K_HEADER_SAMPLES + 150), Some(2));
+        assert_eq!(choose_k(100, K_HEADER_SAMPLES + 99), None);
+        assert_eq!(choose_k(1, 4), None);
+    }
+
+    #[test]
+    fn image_and_audio_round_trip() {
+        let hidden = secret(200);
+
+        let mut image = noise_image(64, 64);
+        embed_matrix_in_image(&hidden, &mut image).unwrap();
+        let (data, _) = extract_matrix_from_image(&image).unwrap();
+        assert_eq!(data, hidden.data);
+
+        let mut audio = noise_audio(4000);
+        embed_matrix_in_audio(&hidden, &mut audio).unwrap();
+        let (data, _) = extract_matrix_from_audio(&audio).unwrap();
+        assert_eq!(data, hidden.data);
+    }
+
+    #[test]
+    fn rejects_a_small_carrier() {
+        let mut image = noise_image(4, 4);
+        assert!(matches!(
+            embed_matrix_in_image(&secret(200), &mut image),
+            Err(Error::Capacity(_))
+        ));
+    }
+
+    #[test]
+    fn reports_missing_or_invalid_parameters_as_not_hidden_data() {
+        assert!(matches!(
+            extract_matrix(&[0u8; K_HEADER_SAMPLES - 1]),
+            Err(Error::NotFound(_))
+        ));
+        assert!(matches!(
+            extract_matrix(&[0u8; 100]),
+            Err(Error::Corrupt(_))
+        ));
+        assert!(matches!(
+            extract_matrix(&[1u8; 100]),
+            Err(Error::Corrupt(_))
+        ));
+    }
+}
@@ -1 +1,9 @@
+pub mod conceal;
+pub mod cost;
+pub mod deniable;
 pub mod extract;
+pub mod lsb;
+pub mod matrix;
+pub mod payload;
+pub mod stc;
+pub mod texture;
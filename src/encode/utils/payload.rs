@@ -0,0 +1,22 @@
+use crate::traits::Encodable;
+
+/// Serializes `hide_content` into the layout shared by all embedding methods:
+/// content type (1 byte), data length (u32, big-endian), data.
+pub fn build_payload<T: Encodable + ?Sized>(hide_content: &T) -> Vec<u8> {
+    let hide_data = hide_content.to_bytes();
+    let hide_data_len = hide_data.len() as u32;
+
+    let mut payload = Vec::with_capacity(1 + 4 + hide_data.len());
+    payload.push(hide_content.content_type().to_u8());
+    payload.extend_from_slice(&hide_data_len.to_be_bytes());
+    payload.extend_from_slice(&hide_data);
+    payload
+}
+
+/// Expands bytes into individual bits (0 or 1), most significant bit first.
+pub fn bytes_to_bits(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|&byte| (0..8).map(move |bit_pos| (byte >> (7 - bit_pos)) & 1))
+        .collect()
+}
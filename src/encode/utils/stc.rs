@@ -0,0 +1,252 @@
+use crate::error::Error;
+use crate::utils::prng::keyed_rng;
+use rand::Rng;
+
+// Syndrome-trellis codes (Filler, Judas and Fridrich, 2011). The parity check
+// matrix is built from a h x w submatrix placed along the diagonal, one block
+// row per message bit, so the Viterbi trellis has 2^h states.
+const CONSTRAINT_HEIGHT: usize = 7;
+const STATES: usize = 1 << CONSTRAINT_HEIGHT;
+
+const COLUMNS_CONTEXT: &str = "stc-columns";
+
+// Columns of the submatrix for a given width. The first and last rows are
+// always set so every column touches both ends of its window.
+fn submatrix_columns(width: usize) -> Vec<usize> {
+    let mut rng = keyed_rng(COLUMNS_CONTEXT, &width.to_string());
+    (0..width)
+        .map(|_| rng.gen_range(0..STATES) | 1 | (STATES >> 1))
+        .collect()
+}
+
+// Block rows near the end of the message have fewer than h rows left.
+fn row_mask(message_len: usize, block: usize) -> usize {
+    let rows_left = message_len - block;
+    if rows_left < CONSTRAINT_HEIGHT {
+        (1 << rows_left) - 1
+    } else {
+        STATES - 1
+    }
+}
+
+// Trellis stages whose paths are held in memory at once, 16 bytes each.
+// Longer messages are traced back a segment at a time, re-running the
+// forward pass from the weights saved at the start of each segment.
+const SEGMENT_STAGES: usize = 1 << 16;
+
+/// Finds the LSB sequence with the lowest total cost whose syndrome is
+/// `message`, where changing the LSB of sample `i` costs `costs[i]`.
+/// Infinite costs mark samples that must not change. Returns new LSBs for
+/// every cover sample; samples past the last block are left as they were.
+pub fn stc_embed(cover: &[u8], costs: &[f64], message: &[u8]) -> Result<Vec<u8>, Error> {
+    stc_embed_segmented(cover, costs, message, SEGMENT_STAGES)
+}
+
+fn stc_embed_segmented(
+    cover: &[u8],
+    costs: &[f64],
+    message: &[u8],
+    segment_stages: usize,
+) -> Result<Vec<u8>, Error> {
+    let message_len = message.len();
+    let width = cover.len().checked_div(message_len).unwrap_or(0);
+    if width == 0 {
+        return Err(Error::Capacity(format!(
+            "Not enough carrier samples for syndrome-trellis coding: {} bits into {} samples",
+            message_len,
+            cover.len()
+        )));
+    }
+    let trellis = Trellis {
+        cover,
+        costs,
+        message,
+        columns: submatrix_columns(width),
+    };
+    let segment_blocks = (segment_stages / width).max(1);
+
+    let mut weights = vec![f64::INFINITY; STATES];
+    weights[0] = 0.0;
+    let mut scratch = vec![0.0; STATES];
+    let mut paths = vec![0u128; segment_blocks * width];
+    let mut checkpoints = Vec::with_capacity(message_len.div_ceil(segment_blocks));
+    for block in 0..message_len {
+        if block % segment_blocks == 0 {
+            checkpoints.push(weights.clone());
+        }
+        trellis.run_block(block, &mut weights, &mut scratch, &mut paths[..width]);
+    }
+
+    if !weights[0].is_finite() {
+        return Err(Error::Capacity(
+            "Syndrome-trellis coding failed: too many samples are excluded from embedding"
+                .to_string(),
+        ));
+    }
+
+    let mut stego = cover.to_vec();
+    let mut state = 0;
+    for (segment, mut weights) in checkpoints.into_iter().enumerate().rev() {
+        let first = segment * segment_blocks;
+        let blocks = first..(first + segment_blocks).min(message_len);
+        for (i, block) in blocks.clone().enumerate() {
+            let paths = &mut paths[i * width..(i + 1) * width];
+            trellis.run_block(block, &mut weights, &mut scratch, paths);
+        }
+
+        for block in blocks.rev() {
+            state = 2 * state + message[block] as usize;
+            let mask = row_mask(message_len, block);
+            for (j, &column) in trellis.columns.iter().enumerate().rev() {
+                let bit = ((paths[(block - first) * width + j] >> state) & 1) as u8;
+                stego[block * width + j] = bit;
+                if bit == 1 {
+                    state ^= column & mask;
+                }
+            }
+        }
+    }
+    Ok(stego)
+}
+
+struct Trellis<'a> {
+    cover: &'a [u8],
+    costs: &'a [f64],
+    message: &'a [u8],
+    columns: Vec<usize>,
+}
+
+impl Trellis<'_> {
+    // Advances `weights` through one block row, writing for each of its
+    // stages which states were reached by setting the sample's LSB.
+    fn run_block(
+        &self,
+        block: usize,
+        weights: &mut Vec<f64>,
+        new_weights: &mut Vec<f64>,
+        paths: &mut [u128],
+    ) {
+        let width = self.columns.len();
+        let mask = row_mask(self.message.len(), block);
+        for (j, (&column, path)) in self.columns.iter().zip(paths).enumerate() {
+            let index = block * width + j;
+            let column = column & mask;
+            let (cost_zero, cost_one) = if self.cover[index] == 0 {
+                (0.0, self.costs[index])
+            } else {
+                (self.costs[index], 0.0)
+            };
+
+            *path = 0;
+            for (state, new_weight) in new_weights.iter_mut().enumerate() {
+                let weight_zero = weights[state] + cost_zero;
+                let weight_one = weights[state ^ column] + cost_one;
+                if weight_one < weight_zero {
+                    *path |= 1 << state;
+                    *new_weight = weight_one;
+                } else {
+                    *new_weight = weight_zero;
+                }
+            }
+            std::mem::swap(weights, new_weights);
+        }
+
+        // Leave the block row: keep the states whose lowest syndrome bit
+        // matches the message, and shift that bit out.
+        let message_bit = self.message[block] as usize;
+        for state in 0..STATES / 2 {
+            weights[state] = weights[2 * state + message_bit];
+        }
+        weights[STATES / 2..].fill(f64::INFINITY);
+    }
+}
+
+/// Computes the `message_len`-bit syndrome of the stego LSBs.
+pub fn stc_extract(stego: &[u8], message_len: usize) -> Vec<u8> {
+    let width = stego.len() / message_len.max(1);
+    let columns = submatrix_columns(width);
+
+    let mut message = vec![0u8; message_len];
+    for block in 0..message_len {
+        let mask = row_mask(message_len, block);
+        for (j, &column) in columns.iter().enumerate() {
+            if stego[block * width + j] == 0 {
+                continue;
+            }
+            let column = column & mask;
+            for (row, bit) in message[block..]
+                .iter_mut()
+                .enumerate()
+                .take(CONSTRAINT_HEIGHT)
+            {
+                *bit ^= ((column >> row) & 1) as u8;
+            }
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn random_bits(rng: &mut ChaCha20Rng, len: usize) -> Vec<u8> {
+        (0..len).map(|_| rng.gen_range(0..2)).collect()
+    }
+
+    #[test]
+    fn syndrome_is_the_message() {
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+        for (cover_len, message_len) in [(1000, 100), (1000, 333), (64, 64), (500, 3)] {
+            let cover = random_bits(&mut rng, cover_len);
+            let costs: Vec<f64> = (0..cover_len).map(|_| rng.gen_range(0.1..10.0)).collect();
+            let message = random_bits(&mut rng, message_len);
+
+            let stego = stc_embed(&cover, &costs, &message).unwrap();
+            assert_eq!(stego.len(), cover.len());
+            assert_eq!(stc_extract(&stego, message_len), message);
+        }
+    }
+
+    #[test]
+    fn keeps_samples_with_infinite_cost() {
+        let mut rng = ChaCha20Rng::seed_from_u64(8);
+        let cover = random_bits(&mut rng, 2000);
+        let costs: Vec<f64> = (0..2000)
+            .map(|i| if i % 4 == 0 { f64::INFINITY } else { 1.0 })
+            .collect();
+        let message = random_bits(&mut rng, 200);
+
+        let stego = stc_embed(&cover, &costs, &message).unwrap();
+        assert_eq!(stc_extract(&stego, 200), message);
+        for i in (0..2000).step_by(4) {
+            assert_eq!(stego[i], cover[i]);
+        }
+    }
+
+    #[test]
+    fn segments_give_the_same_result() {
+        let mut rng = ChaCha20Rng::seed_from_u64(9);
+        let cover = random_bits(&mut rng, 3000);
+        let costs: Vec<f64> = (0..3000).map(|_| rng.gen_range(0.1..10.0)).collect();
+        let message = random_bits(&mut rng, 300);
+
+        let whole = stc_embed(&cover, &costs, &message).unwrap();
+        for segment_stages in [1, 10, 25, 1000] {
+            assert_eq!(
+                stc_embed_segmented(&cover, &costs, &message, segment_stages).unwrap(),
+                whole
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_more_bits_than_samples() {
+        assert!(matches!(
+            stc_embed(&[0; 10], &[1.0; 10], &[1; 11]),
+            Err(Error::Capacity(_))
+        ));
+    }
+}
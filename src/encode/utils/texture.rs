@@ -0,0 +1,32 @@
+use image::RgbImage;
+
+/// Pixels whose channels carry the texture threshold as 8 plain LSBs. They
+/// are never part of the mask.
+pub const PREAMBLE_PIXELS: usize = 3;
+pub const THRESHOLD_BITS: usize = 8;
+
+/// Marks the pixels whose local gradient, summed over the colour channels,
+/// reaches `threshold`. Only the seven high bits of each value are looked at,
+/// so rewriting LSBs never changes the mask and the decoder can recompute it.
+pub fn texture_mask(image: &RgbImage, threshold: u8) -> Vec<bool> {
+    let (width, height) = (image.width() as i64, image.height() as i64);
+    let value = |x: i64, y: i64, channel: usize| -> i32 {
+        let x = x.clamp(0, width - 1) as u32;
+        let y = y.clamp(0, height - 1) as u32;
+        (image.get_pixel(x, y)[channel] & 0xFE) as i32
+    };
+
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .enumerate()
+        .map(|(index, (x, y))| {
+            let gradient: i32 = (0..3)
+                .map(|c| {
+                    (value(x + 1, y, c) - value(x - 1, y, c)).abs()
+                        + (value(x, y + 1, c) - value(x, y - 1, c)).abs()
+                })
+                .sum();
+            index >= PREAMBLE_PIXELS && gradient >= threshold as i32
+        })
+        .collect()
+}
@@ -0,0 +1,94 @@
+use std::io;
+
+/// Errors returned by the library. Variants carrying a `source` keep the
+/// underlying error reachable through `std::error::Error::source`.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The carrier cannot hold the payload with the chosen method.
+    #[error("{0}")]
+    Capacity(String),
+    /// A carrier, hidden file or key file is malformed or unsupported.
+    #[error("{0}")]
+    Format(String),
+    /// The carrier holds no hidden data for the method, key or password.
+    #[error("{0}")]
+    NotFound(String),
+    /// Hidden data was found but is damaged or inconsistent.
+    #[error("{0}")]
+    Corrupt(String),
+    /// Encryption, decryption, signing or verification failed.
+    #[error("{0}")]
+    Crypto(String),
+    /// An option or argument is missing or out of range.
+    #[error("{0}")]
+    InvalidInput(String),
+    /// Reading or writing a file failed.
+    #[error("{context}")]
+    Io {
+        context: String,
+        #[source]
+        source: io::Error,
+    },
+    /// The image codec failed to decode or encode a file.
+    #[error("{context}")]
+    Image {
+        context: String,
+        #[source]
+        source: image::ImageError,
+    },
+    /// The external audio codec (ffmpeg) is missing or failed.
+    #[error("{context}")]
+    Codec {
+        context: String,
+        #[source]
+        source: Option<io::Error>,
+    },
+}
+
+impl Error {
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        Error::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    pub fn image(context: impl Into<String>, source: image::ImageError) -> Self {
+        Error::Image {
+            context: context.into(),
+            source,
+        }
+    }
+
+    pub fn codec(context: impl Into<String>, source: Option<io::Error>) -> Self {
+        Error::Codec {
+            context: context.into(),
+            source,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::load::load_image;
+    use std::error::Error as _;
+
+    #[test]
+    fn keeps_the_underlying_error_as_source() {
+        let error = load_image("/nonexistent/carrier.png").unwrap_err();
+        assert!(matches!(error, Error::Image { .. }));
+        assert_eq!(
+            error.to_string(),
+            "Failed to open image '/nonexistent/carrier.png'"
+        );
+        assert!(error.source().is_some());
+
+        let error = Error::io("Failed to read", io::Error::from(io::ErrorKind::NotFound));
+        let source = error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(source.kind(), io::ErrorKind::NotFound);
+
+        assert!(Error::codec("ffmpeg failed", None).source().is_none());
+        assert!(Error::Corrupt("bad".to_string()).source().is_none());
+    }
+}
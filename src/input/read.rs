@@ -1,4 +1,9 @@
-use clap::{Parser, ValueEnum};
+use crate::method::Setting;
+use crate::registry::Registry;
+use clap::builder::{PossibleValue, PossibleValuesParser};
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, ArgMatches, CommandFactory, Parser};
+use std::collections::HashMap;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -7,19 +12,96 @@ pub struct Args {
     pub command: Command,
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
-pub enum CarrierType {
-    Image,
-    Audio,
+/// `--carrier-type` choices: the carrier formats of the default registry.
+fn carrier_names() -> PossibleValuesParser {
+    PossibleValuesParser::new(
+        Registry::with_defaults()
+            .carriers()
+            .map(|format| PossibleValue::new(format.name))
+            .collect::<Vec<_>>(),
+    )
 }
 
-impl std::fmt::Display for CarrierType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            CarrierType::Image => write!(f, "image"),
-            CarrierType::Audio => write!(f, "audio"),
+/// `--method` choices: the embedding methods of the default registry.
+fn method_names() -> PossibleValuesParser {
+    PossibleValuesParser::new(
+        Registry::with_defaults()
+            .methods()
+            .map(|method| PossibleValue::new(method.name()).help(method.about()))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// The command line, with the settings of the registry's methods added to
+/// `encode` and, where extracting needs them, to `decode`.
+pub fn command(registry: &Registry) -> clap::Command {
+    let mut command = Args::command();
+    for (subcommand, extracting) in [("encode", false), ("decode", true)] {
+        command = command.mut_subcommand(subcommand, |mut subcommand| {
+            for (setting, methods) in method_settings(registry, extracting) {
+                subcommand = subcommand.arg(setting_arg(setting, &methods));
+            }
+            subcommand
+        });
+    }
+    command
+}
+
+/// The method settings given on the command line of the `encode` or `decode`
+/// subcommand `matches`.
+pub fn given_settings(
+    registry: &Registry,
+    matches: &ArgMatches,
+    extracting: bool,
+) -> HashMap<String, String> {
+    method_settings(registry, extracting)
+        .into_iter()
+        .filter(|(setting, _)| matches.value_source(setting.name) == Some(ValueSource::CommandLine))
+        .map(|(setting, _)| {
+            let value = match setting.default {
+                Some(_) => matches.get_one::<String>(setting.name).unwrap().clone(),
+                None => true.to_string(),
+            };
+            (setting.name.to_string(), value)
+        })
+        .collect()
+}
+
+// Each setting declared by the registry's methods, once, with the names of
+// the methods that declare it.
+fn method_settings(registry: &Registry, extracting: bool) -> Vec<(&'static Setting, Vec<&str>)> {
+    let mut settings: Vec<(&'static Setting, Vec<&str>)> = Vec::new();
+    for method in registry.methods() {
+        for setting in method.settings() {
+            if extracting && !setting.on_extract {
+                continue;
+            }
+            match settings
+                .iter_mut()
+                .find(|(known, _)| known.name == setting.name)
+            {
+                Some((_, methods)) => methods.push(method.name()),
+                None => settings.push((setting, vec![method.name()])),
+            }
         }
     }
+    settings
+}
+
+fn setting_arg(setting: &'static Setting, methods: &[&str]) -> Arg {
+    let (last, rest) = methods.split_last().unwrap();
+    let methods = if rest.is_empty() {
+        format!("{} method", last)
+    } else {
+        format!("{} and {} methods", rest.join(", "), last)
+    };
+    let arg = Arg::new(setting.name)
+        .long(setting.name)
+        .help(format!("{} ({})", setting.help, methods));
+    match setting.default {
+        Some(default) => arg.default_value(default),
+        None => arg.action(ArgAction::SetTrue),
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -33,29 +115,142 @@ pub enum Command {
         #[clap(short = 'T', long, value_parser, default_value = "auto")]
         content_type: String,
 
-        /// The carrier file (e.g., PNG for image, WAV for audio)
-        #[clap(short = 'S', long, value_parser)]
-        steg_file: String,
+        /// The carrier file (e.g., PNG for image, WAV/AIFF/CAF for audio); repeat with --threshold or --stripe
+        #[clap(short = 'S', long, value_parser, required = true)]
+        steg_file: Vec<String>,
 
-        /// The output path for the steganography file
-        #[clap(short = 'O', long, value_parser)]
-        output_file: String,
+        /// The output path for the steganography file, one per carrier
+        #[clap(short = 'O', long, value_parser, required = true)]
+        output_file: Vec<String>,
+
+        /// Type of the carrier files (steg_file); inferred from each file's extension when omitted
+        #[clap(short = 'C', long, value_parser = carrier_names())]
+        carrier_type: Option<String>,
+
+        /// Embedding method
+        #[clap(short = 'M', long, value_parser = method_names(), default_value = "lsb")]
+        method: String,
+
+        /// Secret key for the spread and dwt methods; seeds the +/-1 choices of lsb-match and stc and the --fill noise
+        #[clap(long)]
+        key: Option<String>,
+
+        /// Encrypt the hidden data to an X25519 public key (hex, as printed by keygen); repeatable
+        #[clap(long = "recipient", value_name = "PUBKEY")]
+        recipients: Vec<String>,
+
+        /// Split the hidden data into one share per carrier, any THRESHOLD of which reconstruct it
+        #[clap(long)]
+        threshold: Option<u8>,
+
+        /// Spread the hidden data over all carriers in order, sized by their capacity
+        #[clap(long)]
+        stripe: bool,
+
+        /// Sign the hidden data with an Ed25519 key file written by keygen --signing
+        #[clap(long, value_name = "KEYFILE")]
+        sign: Option<String>,
 
-        /// Type of the carrier file (steg_file)
-        #[clap(short = 'C', long, value_enum)]
-        carrier_type: CarrierType,
+        /// Write the --sign signature to FILE instead of hiding it with the data
+        #[clap(long, value_name = "FILE")]
+        detached_signature: Option<String>,
+
+        /// Password protecting the hidden data (deniable method)
+        #[clap(long)]
+        password: Option<String>,
+
+        /// A harmless file to hide alongside, revealed by --decoy-password (deniable method)
+        #[clap(long, value_name = "FILE")]
+        decoy: Option<String>,
+
+        /// Password revealing the decoy file instead of the hidden data (deniable method)
+        #[clap(long)]
+        decoy_password: Option<String>,
     },
     Decode {
-        /// The steganography carrier file containing the hidden data
-        #[clap(short = 'S', long, value_parser)]
-        steg_file: String,
+        /// The steganography carrier file containing the hidden data; repeat to combine shares or stripes
+        #[clap(short = 'S', long, value_parser, required = true)]
+        steg_file: Vec<String>,
 
         /// The output path to save the extracted hidden file (base name)
         #[clap(short = 'O', long, value_parser)]
         output_file: String,
 
-        /// Type of the carrier file (steg_file)
-        #[clap(short = 'C', long, value_enum)]
-        carrier_type: CarrierType,
+        /// Type of the carrier files (steg_file); inferred from each file's extension when omitted
+        #[clap(short = 'C', long, value_parser = carrier_names())]
+        carrier_type: Option<String>,
+
+        /// Embedding method used by the carrier (detected when omitted)
+        #[clap(short = 'M', long, value_parser = method_names())]
+        method: Option<String>,
+
+        /// Secret key used when encoding (spread and dwt methods, and --conceal-header)
+        #[clap(long)]
+        key: Option<String>,
+
+        /// Password for the hidden data; a decoy password reveals the decoy (deniable method)
+        #[clap(long)]
+        password: Option<String>,
+
+        /// Identity file written by keygen, for data encrypted to its public key
+        #[clap(long, value_name = "KEYFILE")]
+        identity: Option<String>,
+
+        /// Verify the hidden data against a signature written by encode --detached-signature
+        #[clap(long, value_name = "FILE")]
+        detached_signature: Option<String>,
+
+        /// Only reconstruct data validly signed by this signer (fingerprint or public key); repeatable
+        #[clap(long = "require-signer", value_name = "SIGNER")]
+        require_signers: Vec<String>,
+    },
+    /// Generate an X25519 keypair for encrypting hidden data to a recipient
+    Keygen {
+        /// The output path for the identity (secret key) file
+        #[clap(short = 'O', long, value_parser)]
+        output_file: String,
+
+        /// Generate an Ed25519 signing key instead of an encryption identity
+        #[clap(long)]
+        signing: bool,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offers_method_settings_on_the_subcommands_that_use_them() {
+        let registry = Registry::with_defaults();
+        let settings = |args: &[&str]| {
+            let matches = command(&registry).try_get_matches_from(args).unwrap();
+            let (name, matches) = matches.subcommand().unwrap();
+            given_settings(&registry, matches, name == "decode")
+        };
+
+        let given = settings(&["steg", "encode", "-H", "a", "-S", "b", "-O", "c", "--zip"]);
+        assert_eq!(
+            given,
+            HashMap::from([("zip".to_string(), "true".to_string())])
+        );
+        let given = settings(&[
+            "steg",
+            "decode",
+            "-S",
+            "b",
+            "-O",
+            "c",
+            "--chip-rate",
+            "2048",
+        ]);
+        assert_eq!(
+            given,
+            HashMap::from([("chip-rate".to_string(), "2048".to_string())])
+        );
+        assert!(settings(&["steg", "decode", "-S", "b", "-O", "c"]).is_empty());
+        assert!(command(&registry)
+            .try_get_matches_from(["steg", "decode", "-S", "b", "-O", "c", "--zip"])
+            .is_err());
+    }
+}
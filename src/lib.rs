@@ -1,6 +1,14 @@
+pub mod carrier;
 pub mod content;
 pub mod decode;
 pub mod encode;
+pub mod error;
 pub mod input;
+pub mod method;
+pub mod registry;
+#[cfg(test)]
+mod testing;
 pub mod traits;
 pub mod utils;
+
+pub use error::Error;
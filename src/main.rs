@@ -1,26 +1,143 @@
-mod content;
-mod decode;
-mod encode;
-mod input;
-mod traits;
-mod utils;
-
-use clap::Parser;
+use clap::FromArgMatches;
 use content::audio::AudioContent;
 use content::image::ImageContent;
+use content::raw::RawContent;
 use decode::common::reconstruct_hidden_content;
-use decode::to_audio::lsb::decode_lsb_from_audio;
-use decode::to_image::lsb::decode_lsb as decode_lsb_from_image;
-use encode::to_audio::lsb::encode_lsb_to_audio;
-use encode::to_image::lsb::encode_lsb as encode_lsb_to_image;
+use error::Error;
+use std::fs;
+use steganography::{content, decode, error, input, method, registry, traits, utils};
 
-use input::read::{Args, CarrierType, Command};
-use std::path::Path;
-use traits::ContentType;
-use utils::load::load_image;
+use input::read::{command, given_settings, Args, Command};
+use method::MethodOptions;
+use registry::Registry;
+use traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use utils::keyfile::encode_hex;
+use utils::seal::{is_sealed, open, parse_recipient, seal, Identity};
+use utils::shamir::{combine, is_share, parse_share, split};
+use utils::sign::{
+    is_signed, parse_signer, sign, sign_detached, verify, verify_detached, SignerKey,
+};
+use utils::stripe::{is_stripe, parse_stripe, reassemble, stripe, stripe_sizes, STRIPE_HEADER_LEN};
 
-fn main() -> Result<(), String> {
-    let args = Args::parse();
+fn resolve_carrier_type(
+    registry: &Registry,
+    carrier_type: Option<&str>,
+    steg_file: &str,
+) -> Result<&'static str, Error> {
+    let format = match carrier_type {
+        Some(name) => registry.carrier(name),
+        None => registry.carrier_for_path(steg_file),
+    };
+    format.map(|format| format.name).ok_or_else(|| {
+        Error::InvalidInput(format!(
+            "Could not determine carrier type for '{}'. Please specify with -C",
+            steg_file
+        ))
+    })
+}
+
+fn registered_method<'r>(
+    registry: &'r Registry,
+    name: &str,
+) -> Result<&'r dyn EmbeddingMethod, Error> {
+    registry
+        .method(name)
+        .ok_or_else(|| Error::InvalidInput(format!("No method registered as {}", name)))
+}
+
+/// Bytes `method` can hide in a carrier, less the stripe header. Used to
+/// size each carrier's piece of striped data.
+fn stripe_capacity(
+    registry: &Registry,
+    method: &str,
+    carrier_type: &str,
+    steg_file: &str,
+    options: &MethodOptions,
+) -> Result<usize, Error> {
+    let carrier = registry.load_carrier(carrier_type, method, steg_file)?;
+    let capacity = registered_method(registry, method)?
+        .capacity(carrier.as_ref(), options)
+        .ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "--stripe needs a method that can tell how much a carrier holds, such as lsb or lsb-match, not {}",
+                method
+            ))
+        })?;
+    Ok(capacity.saturating_sub(STRIPE_HEADER_LEN))
+}
+
+fn load_content(path: &str, content_type: ContentType) -> Result<RawContent, Error> {
+    Ok(match content_type {
+        ContentType::Image => RawContent::from_content(&ImageContent::new(path)?),
+        ContentType::Audio => RawContent::from_content(&AudioContent::new(path)?),
+    })
+}
+
+/// Loads the carrier at `steg_file` and hides `content_to_hide` in it,
+/// returning the carrier for the caller to save.
+fn encode_content(
+    content_to_hide: &dyn Encodable,
+    method: &str,
+    carrier_type: &str,
+    steg_file: &str,
+    options: &MethodOptions,
+    registry: &Registry,
+) -> Result<Box<dyn Carrier>, Error> {
+    let mut carrier = registry.load_carrier(carrier_type, method, steg_file)?;
+    registered_method(registry, method)?.embed(carrier.as_mut(), content_to_hide, options)?;
+    Ok(carrier)
+}
+
+fn decode_content(
+    method: Option<&str>,
+    carrier_type: &str,
+    steg_file: &str,
+    options: &MethodOptions,
+    registry: &Registry,
+) -> Result<(Vec<u8>, ContentType), Error> {
+    let extract = |name: &str| {
+        let carrier = registry.load_carrier(carrier_type, name, steg_file)?;
+        registered_method(registry, name)?.extract(carrier.as_ref(), options)
+    };
+
+    match method {
+        Some(method) => {
+            options.check(registered_method(registry, method)?)?;
+            extract(method)
+        }
+        None => {
+            // A probe that cannot parse the carrier has simply found nothing;
+            // only give up once every method has been tried.
+            let probes = registry.probe_methods();
+            for method in &probes {
+                match extract(method.name()) {
+                    Err(Error::Format(_) | Error::Corrupt(_) | Error::NotFound(_)) => {}
+                    found => {
+                        println!("Found data hidden with the {} method", method.name());
+                        return found;
+                    }
+                }
+            }
+            let names: Vec<_> = probes.iter().map(|method| method.name()).collect();
+            let names = match names.split_last() {
+                Some((last, rest)) if !rest.is_empty() => {
+                    format!("{} or {}", rest.join(", "), last)
+                }
+                _ => names.join(""),
+            };
+            Err(Error::NotFound(format!(
+                "No hidden data found in '{}' with the {} methods",
+                steg_file, names
+            )))
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let registry = Registry::with_defaults();
+    let matches = command(&registry).get_matches();
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let (_, subcommand_matches) = matches.subcommand().expect("a subcommand is required");
 
     match args.command {
         Command::Encode {
@@ -29,81 +146,388 @@ fn main() -> Result<(), String> {
             steg_file,
             output_file,
             carrier_type,
+            method,
+            key,
+            recipients,
+            threshold,
+            stripe: striped,
+            sign: signing_key_file,
+            detached_signature,
+            password,
+            decoy,
+            decoy_password,
         } => {
+            if steg_file.len() != output_file.len() {
+                return Err(Error::InvalidInput(format!(
+                    "Each carrier needs its own output file: got {} -S and {} -O",
+                    steg_file.len(),
+                    output_file.len()
+                )));
+            }
+            if threshold.is_some() && striped {
+                return Err(Error::InvalidInput(
+                    "--threshold and --stripe cannot be combined".to_string(),
+                ));
+            }
+            if threshold.is_none() && !striped && steg_file.len() > 1 {
+                return Err(Error::InvalidInput(
+                    "Hiding data across several carriers requires --threshold or --stripe"
+                        .to_string(),
+                ));
+            }
+            let carrier_types = steg_file
+                .iter()
+                .map(|steg_file| {
+                    resolve_carrier_type(&registry, carrier_type.as_deref(), steg_file)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let embedding_method = registered_method(&registry, &method)?;
+            let decoy = match (decoy, decoy_password) {
+                (None, None) => None,
+                (Some(_), _) | (_, Some(_)) if !embedding_method.takes_decoy() => {
+                    return Err(Error::InvalidInput(format!(
+                        "The {} method cannot hide a decoy",
+                        method
+                    )))
+                }
+                (Some(decoy), Some(decoy_password)) => {
+                    let decoy_type = ContentType::from_path(&decoy).ok_or_else(|| {
+                        Error::InvalidInput(format!(
+                            "Could not determine content type for decoy '{}'",
+                            decoy
+                        ))
+                    })?;
+                    Some((load_content(&decoy, decoy_type)?, decoy_password))
+                }
+                (Some(_), None) => {
+                    return Err(Error::InvalidInput(
+                        "--decoy requires a --decoy-password".to_string(),
+                    ))
+                }
+                (None, Some(_)) => {
+                    return Err(Error::InvalidInput(
+                        "--decoy-password requires a --decoy".to_string(),
+                    ))
+                }
+            };
+            let reused_password = matches!(
+                (&decoy, &password),
+                (Some((_, decoy_password)), Some(password)) if decoy_password == password
+            );
+            if reused_password {
+                return Err(Error::InvalidInput(
+                    "The decoy password must differ from --password".to_string(),
+                ));
+            }
+            let recipients = recipients
+                .iter()
+                .map(|recipient| parse_recipient(recipient))
+                .collect::<Result<Vec<_>, _>>()?;
+            if detached_signature.is_some() && signing_key_file.is_none() {
+                return Err(Error::InvalidInput(
+                    "--detached-signature requires a --sign key".to_string(),
+                ));
+            }
+            let signer = signing_key_file
+                .as_deref()
+                .map(SignerKey::from_file)
+                .transpose()?;
+            let options = MethodOptions {
+                key,
+                password,
+                decoy,
+                settings: given_settings(&registry, subcommand_matches, false),
+            };
+            options.check(embedding_method)?;
             let resolved_content_type = if content_type.to_lowercase() == "auto" {
                 ContentType::from_path(&hide_file).ok_or_else(|| {
-                    format!(
+                    Error::InvalidInput(format!(
                         "Could not determine content type for HIDE_FILE '{}'. Please specify with -T",
                         hide_file
-                    )
+                    ))
                 })?
             } else {
                 match content_type.to_lowercase().as_str() {
                     "image" => ContentType::Image,
                     "audio" => ContentType::Audio,
                     _ => {
-                        return Err(format!(
+                        return Err(Error::InvalidInput(format!(
                             "Unsupported HIDE_FILE content type: {}",
                             content_type
-                        ))
+                        )))
                     }
                 }
             };
 
             println!(
-                "Preparing to hide {:?} data from '{}' into {} carrier '{}'",
-                resolved_content_type, hide_file, carrier_type, steg_file
+                "Preparing to hide {:?} data from '{}' into carrier '{}' using {} method",
+                resolved_content_type,
+                hide_file,
+                steg_file.join("', '"),
+                method
             );
 
-            match resolved_content_type {
-                ContentType::Image => {
-                    let content_to_hide = ImageContent::new(&hide_file);
-                    match carrier_type {
-                        CarrierType::Image => {
-                            encode_lsb_to_image(&content_to_hide, &steg_file, &output_file)?;
-                        }
-                        CarrierType::Audio => {
-                            encode_lsb_to_audio(&content_to_hide, &steg_file, &output_file)?;
-                        }
+            let mut content_to_hide = load_content(&hide_file, resolved_content_type)?;
+            let mut detached = None;
+            if let Some(signer) = &signer {
+                let data = &content_to_hide.data;
+                let content_type = content_to_hide.content_type;
+                match &detached_signature {
+                    Some(signature_file) => {
+                        detached = Some((signature_file, sign_detached(data, content_type, signer)))
                     }
+                    None => content_to_hide.data = sign(data, content_type, signer),
                 }
-                ContentType::Audio => {
-                    let content_to_hide = AudioContent::new(&hide_file)?;
-                    match carrier_type {
-                        CarrierType::Image => {
-                            encode_lsb_to_image(&content_to_hide, &steg_file, &output_file)?;
-                        }
-                        CarrierType::Audio => {
-                            encode_lsb_to_audio(&content_to_hide, &steg_file, &output_file)?;
-                        }
-                    }
+                println!("Signed hidden data as {}", signer.fingerprint());
+            }
+            if !recipients.is_empty() {
+                content_to_hide.data = seal(&content_to_hide.data, &recipients)?;
+                println!("Encrypted hidden data to {} recipient(s)", recipients.len());
+            }
+
+            let contents_to_hide = match threshold {
+                Some(threshold) => {
+                    let total = u8::try_from(steg_file.len()).map_err(|_| {
+                        Error::InvalidInput(
+                            "At most 255 carriers can share hidden data".to_string(),
+                        )
+                    })?;
+                    let shares = split(&content_to_hide.data, threshold, total)?;
+                    println!(
+                        "Split hidden data into {} shares, any {} of which reconstruct it",
+                        total, threshold
+                    );
+                    shares
+                        .into_iter()
+                        .map(|share| RawContent::new(share, content_to_hide.content_type))
+                        .collect()
+                }
+                None if striped => {
+                    let capacities = steg_file
+                        .iter()
+                        .zip(&carrier_types)
+                        .map(|(steg_file, &carrier_type)| {
+                            stripe_capacity(&registry, &method, carrier_type, steg_file, &options)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let sizes = stripe_sizes(content_to_hide.data.len(), &capacities)?;
+                    println!(
+                        "Striping {} bytes of hidden data across {} carriers",
+                        content_to_hide.data.len(),
+                        sizes.len()
+                    );
+                    stripe(&content_to_hide.data, &sizes)?
+                        .into_iter()
+                        .map(|piece| RawContent::new(piece, content_to_hide.content_type))
+                        .collect()
                 }
+                None => vec![content_to_hide],
+            };
+
+            // Embed in every carrier before saving any, so a carrier that is
+            // too small does not leave the others written.
+            let carriers = steg_file
+                .iter()
+                .zip(&carrier_types)
+                .zip(&contents_to_hide)
+                .map(|((steg_file, &carrier_type), content_to_hide)| {
+                    encode_content(
+                        content_to_hide,
+                        &method,
+                        carrier_type,
+                        steg_file,
+                        &options,
+                        &registry,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            for ((steg_file, output_file), carrier) in
+                steg_file.iter().zip(&output_file).zip(&carriers)
+            {
+                carrier.save(output_file)?;
+                println!(
+                    "Successfully hidden data from '{}' in '{}', output to '{}'",
+                    hide_file, steg_file, output_file
+                );
+            }
+            if let Some((signature_file, signature)) = detached {
+                fs::write(signature_file, signature).map_err(|e| {
+                    Error::io(format!("Failed to write signature '{}'", signature_file), e)
+                })?;
+                println!("Detached signature written to '{}'", signature_file);
             }
-            println!(
-                "Successfully hidden data from '{}' in '{}', output to '{}'",
-                hide_file, steg_file, output_file
-            );
         }
         Command::Decode {
             steg_file,
             output_file,
             carrier_type,
+            method,
+            key,
+            password,
+            identity,
+            detached_signature,
+            require_signers,
         } => {
-            println!(
-                "Analyzing {} steganography carrier '{}'",
-                carrier_type, steg_file
-            );
+            let require_signers = require_signers
+                .iter()
+                .map(|signer| parse_signer(signer))
+                .collect::<Result<Vec<_>, _>>()?;
+            let options = MethodOptions {
+                key,
+                password,
+                settings: given_settings(&registry, subcommand_matches, true),
+                ..MethodOptions::default()
+            };
+            let mut decoded = Vec::new();
+            for steg_file in &steg_file {
+                let carrier_type =
+                    resolve_carrier_type(&registry, carrier_type.as_deref(), steg_file)?;
+                println!(
+                    "Analyzing {} steganography carrier '{}'",
+                    carrier_type, steg_file
+                );
+                decoded.push(decode_content(
+                    method.as_deref(),
+                    carrier_type,
+                    steg_file,
+                    &options,
+                    &registry,
+                )?);
+            }
 
-            let (data, hidden_content_type) = match carrier_type {
-                CarrierType::Image => {
-                    let steg_image_carrier = load_image(&steg_file)?;
-                    decode_lsb_from_image(&steg_image_carrier)?
+            let (mut data, hidden_content_type) = if decoded.iter().any(|(data, _)| is_share(data))
+            {
+                let mut shares = Vec::new();
+                for (steg_file, (data, _)) in steg_file.iter().zip(&decoded) {
+                    if !is_share(data) {
+                        return Err(Error::InvalidInput(format!(
+                            "'{}' does not hold a share",
+                            steg_file
+                        )));
+                    }
+                    let share = parse_share(data)?;
+                    println!(
+                        "'{}' holds share {} of {} (any {} reconstruct the hidden data)",
+                        steg_file, share.index, share.total, share.threshold
+                    );
+                    shares.push(share);
+                }
+                (combine(&shares)?, decoded[0].1)
+            } else if decoded.iter().any(|(data, _)| is_stripe(data)) {
+                let mut stripes = Vec::new();
+                for (steg_file, (data, _)) in steg_file.iter().zip(&decoded) {
+                    if !is_stripe(data) {
+                        return Err(Error::InvalidInput(format!(
+                            "'{}' does not hold a piece of striped data",
+                            steg_file
+                        )));
+                    }
+                    let stripe = parse_stripe(data)?;
+                    println!(
+                        "'{}' holds piece {} of {}",
+                        steg_file,
+                        stripe.index as u32 + 1,
+                        stripe.total
+                    );
+                    stripes.push(stripe);
                 }
-                CarrierType::Audio => decode_lsb_from_audio(&steg_file)?,
+                (reassemble(stripes)?, decoded[0].1)
+            } else if decoded.len() == 1 {
+                decoded.remove(0)
+            } else {
+                return Err(Error::InvalidInput(
+                    "Several stego files can only be decoded together when they hold shares or stripes"
+                        .to_string(),
+                ));
+            };
+
+            if is_sealed(&data) {
+                let identity_file = identity.ok_or_else(|| {
+                    Error::InvalidInput(
+                        "The hidden data is encrypted to a recipient; pass --identity".to_string(),
+                    )
+                })?;
+                data = open(&data, &Identity::from_file(&identity_file)?)?;
+                println!("Decrypted hidden data with identity '{}'", identity_file);
+            }
+
+            let signer = if let Some(signature_file) = &detached_signature {
+                let signature = fs::read(signature_file).map_err(|e| {
+                    Error::io(format!("Failed to read signature '{}'", signature_file), e)
+                })?;
+                let signed = verify_detached(&signature, &data, hidden_content_type)?;
+                let validity = if signed.valid { "valid" } else { "INVALID" };
+                println!(
+                    "Signed by {} ({} detached signature)",
+                    signed.fingerprint, validity
+                );
+                Some((signed.fingerprint, signed.valid))
+            } else if is_signed(&data) {
+                let signed = verify(&data, hidden_content_type)?;
+                data = signed.data;
+                let validity = if signed.valid { "valid" } else { "INVALID" };
+                println!("Signed by {} ({} signature)", signed.fingerprint, validity);
+                Some((signed.fingerprint, signed.valid))
+            } else {
+                println!("Hidden data is unsigned");
+                None
             };
+            if !require_signers.is_empty() {
+                match signer {
+                    Some((fingerprint, true)) if require_signers.contains(&fingerprint) => {}
+                    Some((fingerprint, true)) => {
+                        return Err(Error::Crypto(format!(
+                            "Refusing to reconstruct: signer {} is not a required signer",
+                            fingerprint
+                        )))
+                    }
+                    Some((_, false)) => {
+                        return Err(Error::Crypto(
+                            "Refusing to reconstruct: the signature is invalid".to_string(),
+                        ))
+                    }
+                    None => {
+                        return Err(Error::Crypto(
+                            "Refusing to reconstruct: the hidden data is unsigned".to_string(),
+                        ))
+                    }
+                }
+            }
 
             reconstruct_hidden_content(&data, hidden_content_type, &output_file)?;
         }
+        Command::Keygen {
+            output_file,
+            signing,
+        } => {
+            if signing {
+                let signer = SignerKey::generate();
+                signer.write_to_file(&output_file)?;
+                println!("Signing key written to '{}'", output_file);
+                println!("Signer fingerprint: {}", signer.fingerprint());
+            } else {
+                let identity = Identity::generate();
+                identity.write_to_file(&output_file)?;
+                println!("Identity written to '{}'", output_file);
+                println!(
+                    "Public key: {}",
+                    encode_hex(identity.public_key().as_bytes())
+                );
+            }
+        }
     }
     Ok(())
 }
+
+fn main() {
+    if let Err(error) = run() {
+        let mut message = format!("Error: {}", error);
+        let mut source = std::error::Error::source(&error);
+        while let Some(cause) = source {
+            message.push_str(&format!(": {}", cause));
+            source = cause.source();
+        }
+        eprintln!("{}", message);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::decode::to_image::adaptive::extract_adaptive_from_image;
+use crate::encode::to_image::adaptive::embed_adaptive_in_image;
+use crate::error::Error;
+use crate::method::{carrier_as, carrier_as_mut, MethodOptions, Setting};
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use image::RgbImage;
+
+const TEXTURE_THRESHOLD: Setting = Setting {
+    name: "texture-threshold",
+    help: "Minimum local gradient for a pixel to carry data",
+    default: Some("24"),
+    on_extract: false,
+};
+
+/// LSB embedding in textured pixels of image carriers.
+pub struct AdaptiveMethod;
+
+impl EmbeddingMethod for AdaptiveMethod {
+    fn name(&self) -> &'static str {
+        "adaptive"
+    }
+
+    fn about(&self) -> &'static str {
+        "LSB embedding restricted to textured pixels (image carriers only)"
+    }
+
+    fn settings(&self) -> &'static [Setting] {
+        &[TEXTURE_THRESHOLD]
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let image = carrier_as_mut::<RgbImage>(carrier, self.name(), "image")?;
+        embed_adaptive_in_image(hide_content, image, options.value(&TEXTURE_THRESHOLD)?)
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        _options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let image = carrier_as::<RgbImage>(carrier, self.name(), "image")?;
+        extract_adaptive_from_image(image)
+    }
+}
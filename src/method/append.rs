@@ -0,0 +1,72 @@
+use crate::carrier::file::CarrierFile;
+use crate::decode::to_audio::append::extract_append_from_audio;
+use crate::decode::to_image::append::extract_append_from_image;
+use crate::encode::to_audio::append::embed_append_in_audio;
+use crate::encode::to_image::append::embed_append_in_image;
+use crate::error::Error;
+use crate::method::{carrier_as, carrier_as_mut, MethodOptions, Setting};
+use crate::registry::CarrierFormat;
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+
+const ZIP: Setting = Setting {
+    name: "zip",
+    help: "Make the output a ZIP polyglot that also opens as an archive",
+    default: None,
+    on_extract: false,
+};
+
+/// Appends the payload after the logical end of the carrier file.
+pub struct AppendMethod;
+
+impl EmbeddingMethod for AppendMethod {
+    fn name(&self) -> &'static str {
+        "append"
+    }
+
+    fn about(&self) -> &'static str {
+        "Append data after the carrier's logical end (PNG, JPEG, GIF, WAV, MP3)"
+    }
+
+    fn settings(&self) -> &'static [Setting] {
+        &[ZIP]
+    }
+
+    fn probe_order(&self) -> Option<u32> {
+        Some(1)
+    }
+
+    /// Reads the file as is, without decoding it.
+    fn load_carrier(&self, format: &CarrierFormat, path: &str) -> Result<Box<dyn Carrier>, Error> {
+        Ok(Box::new(CarrierFile::read(path, format.content_type)?))
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let file = carrier_as_mut::<CarrierFile>(carrier, self.name(), "unencoded file")?;
+        let as_zip = options.flag(&ZIP);
+        file.bytes = match file.content_type {
+            ContentType::Image => embed_append_in_image(hide_content, &file.bytes, as_zip)?,
+            ContentType::Audio => embed_append_in_audio(hide_content, &file.bytes, as_zip)?,
+        };
+        Ok(())
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        _options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let file = carrier_as::<CarrierFile>(carrier, self.name(), "unencoded file")?;
+        let found = match file.content_type {
+            ContentType::Image => extract_append_from_image(&file.bytes)?,
+            ContentType::Audio => extract_append_from_audio(&file.bytes)?,
+        };
+        found.ok_or_else(|| {
+            Error::NotFound("No data found after the end of the carrier".to_string())
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use crate::decode::to_image::bpcs::extract_bpcs_from_image;
+use crate::encode::to_image::bpcs::embed_bpcs_in_image;
+use crate::error::Error;
+use crate::method::{carrier_as, carrier_as_mut, MethodOptions, Setting};
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use image::RgbImage;
+
+const COMPLEXITY_THRESHOLD: Setting = Setting {
+    name: "complexity-threshold",
+    help: "Minimum bit-plane block complexity, in (0, 0.5]",
+    default: Some("0.3"),
+    on_extract: true,
+};
+
+/// Bit-plane complexity segmentation on image carriers.
+pub struct BpcsMethod;
+
+impl EmbeddingMethod for BpcsMethod {
+    fn name(&self) -> &'static str {
+        "bpcs"
+    }
+
+    fn about(&self) -> &'static str {
+        "Replace noise-like bit-plane blocks with payload blocks (image carriers only)"
+    }
+
+    fn settings(&self) -> &'static [Setting] {
+        &[COMPLEXITY_THRESHOLD]
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let image = carrier_as_mut::<RgbImage>(carrier, self.name(), "image")?;
+        embed_bpcs_in_image(hide_content, image, options.value(&COMPLEXITY_THRESHOLD)?)
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let image = carrier_as::<RgbImage>(carrier, self.name(), "image")?;
+        extract_bpcs_from_image(image, options.value(&COMPLEXITY_THRESHOLD)?)
+    }
+}
@@ -0,0 +1,59 @@
+use crate::carrier::file::CarrierFile;
+use crate::decode::to_audio::chunk::extract_chunk_from_audio;
+use crate::decode::to_image::chunk::extract_chunk_from_image;
+use crate::encode::to_audio::chunk::embed_chunk_in_audio;
+use crate::encode::to_image::chunk::embed_chunk_in_image;
+use crate::error::Error;
+use crate::method::{carrier_as, carrier_as_mut, MethodOptions};
+use crate::registry::CarrierFormat;
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+
+/// Stores the payload in a private metadata chunk of the carrier file.
+pub struct ChunkMethod;
+
+impl EmbeddingMethod for ChunkMethod {
+    fn name(&self) -> &'static str {
+        "chunk"
+    }
+
+    fn about(&self) -> &'static str {
+        "Store data in a private metadata chunk (PNG, JPEG, WAV, AIFF, CAF)"
+    }
+
+    fn probe_order(&self) -> Option<u32> {
+        Some(0)
+    }
+
+    /// Reads the file as is, without decoding it.
+    fn load_carrier(&self, format: &CarrierFormat, path: &str) -> Result<Box<dyn Carrier>, Error> {
+        Ok(Box::new(CarrierFile::read(path, format.content_type)?))
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        _options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let file = carrier_as_mut::<CarrierFile>(carrier, self.name(), "unencoded file")?;
+        file.bytes = match file.content_type {
+            ContentType::Image => embed_chunk_in_image(hide_content, &file.bytes)?,
+            ContentType::Audio => embed_chunk_in_audio(hide_content, &file.bytes)?,
+        };
+        Ok(())
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        _options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let file = carrier_as::<CarrierFile>(carrier, self.name(), "unencoded file")?;
+        let found = match file.content_type {
+            ContentType::Image => extract_chunk_from_image(&file.bytes)?,
+            ContentType::Audio => extract_chunk_from_audio(&file.bytes)?,
+        };
+        found
+            .ok_or_else(|| Error::NotFound("No hidden data chunk found in the carrier".to_string()))
+    }
+}
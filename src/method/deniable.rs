@@ -0,0 +1,65 @@
+use crate::decode::to_audio::deniable::extract_deniable_from_audio;
+use crate::decode::to_image::deniable::extract_deniable_from_image;
+use crate::encode::to_audio::deniable::embed_deniable_in_audio;
+use crate::encode::to_image::deniable::embed_deniable_in_image;
+use crate::error::Error;
+use crate::method::{unsupported, MethodOptions};
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use crate::utils::audio::PcmAudio;
+use image::RgbImage;
+use std::any::Any;
+
+/// Password-encrypted LSB slots on image or audio carriers, with an optional
+/// decoy under a second password.
+pub struct DeniableMethod;
+
+impl EmbeddingMethod for DeniableMethod {
+    fn name(&self) -> &'static str {
+        "deniable"
+    }
+
+    fn about(&self) -> &'static str {
+        "Password-encrypted LSB slots, optionally holding a decoy under a second password"
+    }
+
+    fn takes_decoy(&self) -> bool {
+        true
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let password = options.require_password(self.name())?;
+        let decoy = options
+            .decoy
+            .as_ref()
+            .map(|(content, password)| (content, password.as_str()));
+        let carrier: &mut dyn Any = carrier;
+        if let Some(image) = carrier.downcast_mut::<RgbImage>() {
+            embed_deniable_in_image(hide_content, image, password, decoy)
+        } else if let Some(audio) = carrier.downcast_mut::<PcmAudio>() {
+            embed_deniable_in_audio(hide_content, audio, password, decoy)
+        } else {
+            Err(unsupported(self.name(), "image or audio"))
+        }
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let password = options.require_password(self.name())?;
+        let carrier: &dyn Any = carrier;
+        if let Some(image) = carrier.downcast_ref::<RgbImage>() {
+            extract_deniable_from_image(image, password)
+        } else if let Some(audio) = carrier.downcast_ref::<PcmAudio>() {
+            extract_deniable_from_audio(audio, password)
+        } else {
+            Err(unsupported(self.name(), "image or audio"))
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use crate::decode::to_image::dwt::extract_dwt_from_image;
+use crate::encode::to_image::dwt::embed_dwt_in_image;
+use crate::error::Error;
+use crate::method::{carrier_as, carrier_as_mut, MethodOptions};
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use image::RgbImage;
+
+/// Keyed wavelet-domain watermark on image carriers. It survives JPEG
+/// recompression and rescaling, but not cropping, and its only error
+/// correction is a majority vote over repeated blocks.
+pub struct DwtMethod;
+
+impl EmbeddingMethod for DwtMethod {
+    fn name(&self) -> &'static str {
+        "dwt"
+    }
+
+    fn about(&self) -> &'static str {
+        "Robust keyed watermark in the wavelet domain (image carriers only)"
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let image = carrier_as_mut::<RgbImage>(carrier, self.name(), "image")?;
+        embed_dwt_in_image(hide_content, image, options.require_key(self.name())?)
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let image = carrier_as::<RgbImage>(carrier, self.name(), "image")?;
+        let watermark = extract_dwt_from_image(image, options.require_key(self.name())?)?;
+        Ok((watermark.data, watermark.content_type))
+    }
+}
@@ -0,0 +1,49 @@
+use crate::decode::to_audio::echo::extract_echo_from_audio;
+use crate::encode::to_audio::echo::embed_echo_in_audio;
+use crate::error::Error;
+use crate::method::{carrier_as, carrier_as_mut, MethodOptions, Setting};
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use crate::utils::audio::PcmAudio;
+
+const ECHO_AMPLITUDE: Setting = Setting {
+    name: "echo-amplitude",
+    help: "Echo level relative to the carrier, in (0, 1]; higher is more robust but more audible",
+    default: Some("0.3"),
+    on_extract: false,
+};
+
+/// Echo hiding on audio carriers.
+pub struct EchoMethod;
+
+impl EmbeddingMethod for EchoMethod {
+    fn name(&self) -> &'static str {
+        "echo"
+    }
+
+    fn about(&self) -> &'static str {
+        "Add a faint echo whose delay encodes each bit (audio carriers only)"
+    }
+
+    fn settings(&self) -> &'static [Setting] {
+        &[ECHO_AMPLITUDE]
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let audio = carrier_as_mut::<PcmAudio>(carrier, self.name(), "audio")?;
+        embed_echo_in_audio(hide_content, audio, options.value(&ECHO_AMPLITUDE)?)
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        _options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let audio = carrier_as::<PcmAudio>(carrier, self.name(), "audio")?;
+        extract_echo_from_audio(audio)
+    }
+}
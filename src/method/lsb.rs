@@ -0,0 +1,274 @@
+use crate::decode::common::{bits_to_bytes, parse_payload};
+use crate::encode::utils::conceal::{
+    conceal_payload, extract_concealed, CONCEALED_HEADER_BITS, FLAG_FILLED,
+};
+use crate::encode::utils::lsb::{fill_rng, LsbEmbedding};
+use crate::encode::utils::payload::{build_payload, bytes_to_bits};
+use crate::error::Error;
+use crate::method::{MethodOptions, Setting};
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use rand::Rng;
+use rand_chacha::ChaCha20Rng;
+
+const HEADER_BITS: usize = (1 + 4) * 8;
+
+const FILL: Setting = Setting {
+    name: "fill",
+    help: "Randomize every LSB after the hidden data, keyed by --key if given",
+    default: None,
+    on_extract: false,
+};
+
+const CONCEAL_HEADER: Setting = Setting {
+    name: "conceal-header",
+    help: "The content type and length are encrypted with --key and the data starts at a key-derived position",
+    default: None,
+    on_extract: true,
+};
+
+/// LSB replacement (`lsb`) or matching (`lsb-match`) on any [`Carrier`].
+/// Matching and `--fill` are driven by the key when one is given, and by a
+/// random seed otherwise.
+pub struct LsbMethod {
+    pub matching: bool,
+}
+
+impl EmbeddingMethod for LsbMethod {
+    fn name(&self) -> &'static str {
+        if self.matching {
+            "lsb-match"
+        } else {
+            "lsb"
+        }
+    }
+
+    fn about(&self) -> &'static str {
+        if self.matching {
+            "Like lsb, but mismatched values are randomly incremented or decremented"
+        } else {
+            "Hide data in the least significant bit of each pixel channel or sample"
+        }
+    }
+
+    fn settings(&self) -> &'static [Setting] {
+        &[FILL, CONCEAL_HEADER]
+    }
+
+    /// Plain LSB is tried last when decoding without a method; lsb-match
+    /// writes the same format, so it need not be tried as well.
+    fn probe_order(&self) -> Option<u32> {
+        (!self.matching).then_some(2)
+    }
+
+    fn capacity(&self, carrier: &dyn Carrier, options: &MethodOptions) -> Option<usize> {
+        let header_bits = if options.flag(&CONCEAL_HEADER) {
+            CONCEALED_HEADER_BITS
+        } else {
+            HEADER_BITS
+        };
+        Some(carrier.capacity().saturating_sub(header_bits) / 8)
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let key = options.key.as_deref();
+        let embedding = if self.matching {
+            LsbEmbedding::matching(key)
+        } else {
+            LsbEmbedding::Replacement
+        };
+        let fill = options.flag(&FILL).then(|| fill_rng(key));
+        embed_lsb_in_carrier(
+            carrier,
+            hide_content,
+            embedding,
+            fill,
+            conceal_key(options)?,
+        )
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        extract_lsb_from_carrier(carrier, conceal_key(options)?)
+    }
+}
+
+fn conceal_key(options: &MethodOptions) -> Result<Option<&str>, Error> {
+    if !options.flag(&CONCEAL_HEADER) {
+        return Ok(None);
+    }
+    options
+        .key
+        .as_deref()
+        .map(Some)
+        .ok_or_else(|| Error::InvalidInput("--conceal-header requires a secret --key".to_string()))
+}
+
+/// Hides `bits` in the values from `start` on, wrapping around to the first
+/// value. Mismatched LSBs are replaced or matched as `embedding` says.
+fn write_lsbs(carrier: &mut dyn Carrier, start: usize, bits: &[u8], embedding: &mut LsbEmbedding) {
+    match embedding {
+        LsbEmbedding::Replacement => carrier.write_bits(start, bits),
+        LsbEmbedding::Matching(rng) => {
+            let current = carrier.read_bits();
+            for (i, &bit) in bits.iter().enumerate() {
+                let index = (start + i) % current.len();
+                if current[index] != bit {
+                    carrier.step(index, rng.gen_bool(0.5));
+                }
+            }
+        }
+    }
+}
+
+/// Hides `hide_content` in the carrier's LSBs from the first value on, or
+/// from a keyed offset with a whitened header when `conceal_key` is given.
+/// With `fill`, every LSB after the payload is overwritten with noise.
+pub fn embed_lsb_in_carrier<T: Encodable + ?Sized>(
+    carrier: &mut dyn Carrier,
+    hide_content: &T,
+    mut embedding: LsbEmbedding,
+    fill: Option<ChaCha20Rng>,
+    conceal_key: Option<&str>,
+) -> Result<(), Error> {
+    let capacity = carrier.capacity();
+    let (offset, bits) = match conceal_key {
+        Some(key) => {
+            let flags = if fill.is_some() { FLAG_FILLED } else { 0 };
+            let concealed = conceal_payload(hide_content, key, capacity, flags);
+            (concealed.offset, concealed.bits)
+        }
+        None => (0, bytes_to_bits(&build_payload(hide_content))),
+    };
+
+    if bits.len() > capacity {
+        return Err(Error::Capacity(format!(
+            "Carrier not large enough to hide {} bytes of data: needed {} bits, available {} bits",
+            hide_content.to_bytes().len(),
+            bits.len(),
+            capacity
+        )));
+    }
+
+    write_lsbs(carrier, offset, &bits, &mut embedding);
+    if let Some(mut rng) = fill {
+        let noise: Vec<u8> = (bits.len()..capacity)
+            .map(|_| rng.gen_bool(0.5) as u8)
+            .collect();
+        write_lsbs(carrier, offset + bits.len(), &noise, &mut embedding);
+    }
+
+    Ok(())
+}
+
+/// Reads a payload written by [`embed_lsb_in_carrier`] with the same
+/// `conceal_key`.
+pub fn extract_lsb_from_carrier(
+    carrier: &dyn Carrier,
+    conceal_key: Option<&str>,
+) -> Result<(Vec<u8>, ContentType), Error> {
+    let bits = carrier.read_bits();
+    if let Some(key) = conceal_key {
+        return extract_concealed(&bits, key);
+    }
+
+    if bits.len() < HEADER_BITS {
+        return Err(Error::NotFound(
+            "Carrier too small to contain hidden data".to_string(),
+        ));
+    }
+
+    let header = bits_to_bytes(&bits[..HEADER_BITS]);
+    let content_type = ContentType::from_u8(header[0])
+        .ok_or_else(|| Error::Corrupt(format!("Invalid content type byte: {}", header[0])))?;
+    println!("Detected content type: {:?}", content_type);
+
+    let data_len = u32::from_be_bytes(header[1..5].try_into().unwrap()) as usize;
+    println!("Detected data length: {} bytes", data_len);
+
+    let total_bits = data_len
+        .checked_mul(8)
+        .and_then(|n| n.checked_add(HEADER_BITS))
+        .filter(|&n| n <= bits.len())
+        .ok_or_else(|| {
+            Error::Corrupt(format!(
+                "Corrupted data: claimed data length {} exceeds the {} bits the carrier holds",
+                data_len,
+                bits.len()
+            ))
+        })?;
+
+    parse_payload(&bits_to_bytes(&bits[..total_bits]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{noise_image, secret};
+    use image::RgbImage;
+
+    fn grey() -> RgbImage {
+        RgbImage::from_pixel(64, 64, image::Rgb([128, 128, 128]))
+    }
+
+    #[test]
+    fn fill_randomizes_the_lsbs_after_the_payload() {
+        let hidden = secret(100);
+        let mut image = grey();
+        let fill = Some(fill_rng(Some("key")));
+        embed_lsb_in_carrier(&mut image, &hidden, LsbEmbedding::Replacement, fill, None).unwrap();
+
+        let (data, _) = extract_lsb_from_carrier(&image, None).unwrap();
+        assert_eq!(data, hidden.data);
+        let tail = &image.read_bits()[HEADER_BITS + 800..];
+        let ones = tail.iter().filter(|&&bit| bit == 1).count() as f64 / tail.len() as f64;
+        assert!(
+            (0.47..0.53).contains(&ones),
+            "{} of the tail LSBs are set",
+            ones
+        );
+    }
+
+    #[test]
+    fn keyed_fill_is_reproducible() {
+        let embed_with = |key| {
+            let mut image = grey();
+            let fill = Some(fill_rng(Some(key)));
+            embed_lsb_in_carrier(
+                &mut image,
+                &secret(10),
+                LsbEmbedding::Replacement,
+                fill,
+                None,
+            )
+            .unwrap();
+            image
+        };
+        assert_eq!(embed_with("a"), embed_with("a"));
+        assert_ne!(embed_with("a"), embed_with("b"));
+    }
+
+    #[test]
+    fn fill_with_matching_moves_values_by_one() {
+        let original = noise_image(32, 32);
+        let mut image = original.clone();
+        let embedding = LsbEmbedding::matching(Some("key"));
+        let fill = Some(fill_rng(Some("key")));
+        embed_lsb_in_carrier(&mut image, &secret(50), embedding, fill, None).unwrap();
+
+        assert!(original
+            .as_raw()
+            .iter()
+            .zip(image.as_raw())
+            .all(|(&a, &b)| a.abs_diff(b) <= 1));
+        let (data, _) = extract_lsb_from_carrier(&image, None).unwrap();
+        assert_eq!(data, secret(50).data);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::decode::to_audio::matrix::extract_matrix_from_audio;
+use crate::decode::to_image::matrix::extract_matrix_from_image;
+use crate::encode::to_audio::matrix::embed_matrix_in_audio;
+use crate::encode::to_image::matrix::embed_matrix_in_image;
+use crate::error::Error;
+use crate::method::{unsupported, MethodOptions};
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use crate::utils::audio::PcmAudio;
+use image::RgbImage;
+use std::any::Any;
+
+/// Hamming-code matrix embedding on image or audio carriers.
+pub struct MatrixMethod;
+
+impl EmbeddingMethod for MatrixMethod {
+    fn name(&self) -> &'static str {
+        "matrix"
+    }
+
+    fn about(&self) -> &'static str {
+        "LSB embedding through Hamming codes, changing fewer values for small payloads"
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        _options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let carrier: &mut dyn Any = carrier;
+        if let Some(image) = carrier.downcast_mut::<RgbImage>() {
+            embed_matrix_in_image(hide_content, image)
+        } else if let Some(audio) = carrier.downcast_mut::<PcmAudio>() {
+            embed_matrix_in_audio(hide_content, audio)
+        } else {
+            Err(unsupported(self.name(), "image or audio"))
+        }
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        _options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let carrier: &dyn Any = carrier;
+        if let Some(image) = carrier.downcast_ref::<RgbImage>() {
+            extract_matrix_from_image(image)
+        } else if let Some(audio) = carrier.downcast_ref::<PcmAudio>() {
+            extract_matrix_from_audio(audio)
+        } else {
+            Err(unsupported(self.name(), "image or audio"))
+        }
+    }
+}
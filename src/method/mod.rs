@@ -0,0 +1,165 @@
+use crate::content::raw::RawContent;
+use crate::error::Error;
+use crate::traits::{Carrier, EmbeddingMethod};
+use std::any::Any;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub mod adaptive;
+pub mod append;
+pub mod bpcs;
+pub mod chunk;
+pub mod deniable;
+pub mod dwt;
+pub mod echo;
+pub mod lsb;
+pub mod matrix;
+pub mod parity;
+pub mod phase;
+pub mod pvd;
+pub mod spread;
+pub mod stc;
+
+/// A setting a method reads from [`MethodOptions`], offered on the command
+/// line as `--<name>`.
+pub struct Setting {
+    pub name: &'static str,
+    /// Help shown on the command line, without the method names.
+    pub help: &'static str,
+    /// Value used when the setting is not given, or `None` for an on/off flag.
+    pub default: Option<&'static str>,
+    /// The setting is also needed to extract, not only to embed.
+    pub on_extract: bool,
+}
+
+/// Settings for one embed or extract call. Each method reads the ones it
+/// uses and ignores the rest.
+#[derive(Default)]
+pub struct MethodOptions {
+    /// Secret key for keyed methods, LSB matching and the concealed header.
+    pub key: Option<String>,
+    pub password: Option<String>,
+    /// A decoy and the password revealing it, for methods that take one.
+    pub decoy: Option<(RawContent, String)>,
+    /// Values of method [`Setting`]s by name. Flags that are on hold `true`.
+    pub settings: HashMap<String, String>,
+}
+
+impl MethodOptions {
+    /// Sets the setting called `name` to `value`.
+    pub fn with(mut self, name: &str, value: impl ToString) -> Self {
+        self.settings.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn require_key(&self, method: &str) -> Result<&str, Error> {
+        self.key.as_deref().ok_or_else(|| {
+            Error::InvalidInput(format!("The {} method requires a secret --key", method))
+        })
+    }
+
+    pub fn require_password(&self, method: &str) -> Result<&str, Error> {
+        self.password.as_deref().ok_or_else(|| {
+            Error::InvalidInput(format!("The {} method requires a --password", method))
+        })
+    }
+
+    /// The value of `setting`, or its default when it was not given.
+    pub fn value<T: FromStr>(&self, setting: &Setting) -> Result<T, Error> {
+        let value = self
+            .settings
+            .get(setting.name)
+            .map(String::as_str)
+            .or(setting.default)
+            .ok_or_else(|| Error::InvalidInput(format!("--{} is required", setting.name)))?;
+        value.parse().map_err(|_| {
+            Error::InvalidInput(format!("Invalid --{} value '{}'", setting.name, value))
+        })
+    }
+
+    pub fn flag(&self, setting: &Setting) -> bool {
+        self.settings
+            .get(setting.name)
+            .is_some_and(|value| value != "false")
+    }
+
+    /// Fails on options `method` does not use: settings it does not declare,
+    /// or a decoy it cannot hide.
+    pub fn check(&self, method: &dyn EmbeddingMethod) -> Result<(), Error> {
+        let mut names: Vec<_> = self.settings.keys().collect();
+        names.sort();
+        let declared = method.settings();
+        if let Some(name) = names
+            .into_iter()
+            .find(|name| !declared.iter().any(|setting| setting.name == name.as_str()))
+        {
+            return Err(Error::InvalidInput(format!(
+                "--{} is not supported by the {} method",
+                name,
+                method.name()
+            )));
+        }
+        if self.decoy.is_some() && !method.takes_decoy() {
+            return Err(Error::InvalidInput(format!(
+                "The {} method cannot hide a decoy",
+                method.name()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Every method that ships with the crate, in the order the command-line
+/// help lists them.
+pub fn builtin_methods() -> Vec<Box<dyn EmbeddingMethod>> {
+    vec![
+        Box::new(lsb::LsbMethod { matching: false }),
+        Box::new(lsb::LsbMethod { matching: true }),
+        Box::new(matrix::MatrixMethod),
+        Box::new(stc::StcMethod),
+        Box::new(adaptive::AdaptiveMethod),
+        Box::new(pvd::PvdMethod),
+        Box::new(bpcs::BpcsMethod),
+        Box::new(dwt::DwtMethod),
+        Box::new(deniable::DeniableMethod),
+        Box::new(chunk::ChunkMethod),
+        Box::new(append::AppendMethod),
+        Box::new(phase::PhaseMethod),
+        Box::new(echo::EchoMethod),
+        Box::new(spread::SpreadMethod),
+        Box::new(parity::ParityMethod),
+    ]
+}
+
+/// The carrier as the concrete type a method written for one kind of medium
+/// works on, or an error naming the kind it needs.
+pub fn carrier_as<'a, C: Carrier>(
+    carrier: &'a dyn Carrier,
+    method: &str,
+    kind: &str,
+) -> Result<&'a C, Error> {
+    let carrier: &dyn Any = carrier;
+    carrier
+        .downcast_ref::<C>()
+        .ok_or_else(|| unsupported(method, kind))
+}
+
+/// Like [`carrier_as`], for a carrier being embedded in.
+pub fn carrier_as_mut<'a, C: Carrier>(
+    carrier: &'a mut dyn Carrier,
+    method: &str,
+    kind: &str,
+) -> Result<&'a mut C, Error> {
+    let carrier: &mut dyn Any = carrier;
+    carrier
+        .downcast_mut::<C>()
+        .ok_or_else(|| unsupported(method, kind))
+}
+
+/// The error for a carrier a method cannot work on.
+pub fn unsupported(method: &str, kind: &str) -> Error {
+    Error::InvalidInput(format!(
+        "The {} method is only supported for {} carriers",
+        method, kind
+    ))
+}
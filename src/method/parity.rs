@@ -0,0 +1,49 @@
+use crate::decode::to_audio::parity::extract_parity_from_audio;
+use crate::encode::to_audio::parity::embed_parity_in_audio;
+use crate::error::Error;
+use crate::method::{carrier_as, carrier_as_mut, MethodOptions, Setting};
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use crate::utils::audio::PcmAudio;
+
+const REGION_SIZE: Setting = Setting {
+    name: "region-size",
+    help: "Samples per hidden bit",
+    default: Some("4"),
+    on_extract: true,
+};
+
+/// Parity coding on audio carriers.
+pub struct ParityMethod;
+
+impl EmbeddingMethod for ParityMethod {
+    fn name(&self) -> &'static str {
+        "parity"
+    }
+
+    fn about(&self) -> &'static str {
+        "Encode each bit as the parity of a region of samples (audio carriers only)"
+    }
+
+    fn settings(&self) -> &'static [Setting] {
+        &[REGION_SIZE]
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let audio = carrier_as_mut::<PcmAudio>(carrier, self.name(), "audio")?;
+        embed_parity_in_audio(hide_content, audio, options.value(&REGION_SIZE)?)
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let audio = carrier_as::<PcmAudio>(carrier, self.name(), "audio")?;
+        extract_parity_from_audio(audio, options.value(&REGION_SIZE)?)
+    }
+}
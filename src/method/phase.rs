@@ -0,0 +1,38 @@
+use crate::decode::to_audio::phase::extract_phase_from_audio;
+use crate::encode::to_audio::phase::embed_phase_in_audio;
+use crate::error::Error;
+use crate::method::{carrier_as, carrier_as_mut, MethodOptions};
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use crate::utils::audio::PcmAudio;
+
+/// Phase coding on audio carriers.
+pub struct PhaseMethod;
+
+impl EmbeddingMethod for PhaseMethod {
+    fn name(&self) -> &'static str {
+        "phase"
+    }
+
+    fn about(&self) -> &'static str {
+        "Encode bits in the phase of the first audio segment (audio carriers only)"
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        _options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let audio = carrier_as_mut::<PcmAudio>(carrier, self.name(), "audio")?;
+        embed_phase_in_audio(hide_content, audio)
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        _options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let audio = carrier_as::<PcmAudio>(carrier, self.name(), "audio")?;
+        extract_phase_from_audio(audio)
+    }
+}
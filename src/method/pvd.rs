@@ -0,0 +1,38 @@
+use crate::decode::to_image::pvd::extract_pvd_from_image;
+use crate::encode::to_image::pvd::embed_pvd_in_image;
+use crate::error::Error;
+use crate::method::{carrier_as, carrier_as_mut, MethodOptions};
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use image::RgbImage;
+
+/// Pixel-value differencing on image carriers.
+pub struct PvdMethod;
+
+impl EmbeddingMethod for PvdMethod {
+    fn name(&self) -> &'static str {
+        "pvd"
+    }
+
+    fn about(&self) -> &'static str {
+        "Pixel-value differencing, hiding more bits in busy pixel pairs (image carriers only)"
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        _options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let image = carrier_as_mut::<RgbImage>(carrier, self.name(), "image")?;
+        embed_pvd_in_image(hide_content, image)
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        _options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let image = carrier_as::<RgbImage>(carrier, self.name(), "image")?;
+        extract_pvd_from_image(image)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::decode::to_audio::spread::extract_spread_from_audio;
+use crate::encode::to_audio::spread::{embed_spread_in_audio, SpreadOptions};
+use crate::error::Error;
+use crate::method::{carrier_as, carrier_as_mut, MethodOptions, Setting};
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use crate::utils::audio::PcmAudio;
+
+const CHIP_RATE: Setting = Setting {
+    name: "chip-rate",
+    help: "Samples each bit is spread over; the carrier holds one bit per that many samples, e.g. only about 21 bytes in 20 s of 44.1 kHz audio at the default, so lower it for larger payloads at some cost in robustness",
+    default: Some("4096"),
+    on_extract: true,
+};
+
+const STRENGTH: Setting = Setting {
+    name: "strength",
+    help: "Watermark level relative to the carrier's RMS level",
+    default: Some("0.05"),
+    on_extract: false,
+};
+
+/// Keyed spread-spectrum watermark on audio carriers.
+pub struct SpreadMethod;
+
+impl EmbeddingMethod for SpreadMethod {
+    fn name(&self) -> &'static str {
+        "spread"
+    }
+
+    fn about(&self) -> &'static str {
+        "Spread each bit over a keyed pseudo-noise sequence (audio carriers only)"
+    }
+
+    fn settings(&self) -> &'static [Setting] {
+        &[CHIP_RATE, STRENGTH]
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let audio = carrier_as_mut::<PcmAudio>(carrier, self.name(), "audio")?;
+        embed_spread_in_audio(
+            hide_content,
+            audio,
+            &SpreadOptions {
+                key: options.require_key(self.name())?,
+                chip_rate: options.value(&CHIP_RATE)?,
+                strength: options.value(&STRENGTH)?,
+            },
+        )
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let audio = carrier_as::<PcmAudio>(carrier, self.name(), "audio")?;
+        let watermark = extract_spread_from_audio(
+            audio,
+            options.require_key(self.name())?,
+            options.value(&CHIP_RATE)?,
+        )?;
+        Ok((watermark.data, watermark.content_type))
+    }
+}
@@ -0,0 +1,43 @@
+use crate::decode::to_image::stc::extract_stc_from_image;
+use crate::encode::to_image::stc::embed_stc_in_image;
+use crate::encode::utils::lsb::LsbEmbedding;
+use crate::error::Error;
+use crate::method::{carrier_as, carrier_as_mut, MethodOptions};
+use crate::traits::{Carrier, ContentType, EmbeddingMethod, Encodable};
+use image::RgbImage;
+
+/// Syndrome-trellis coding on image carriers, with +/-1 changes seeded by the key.
+pub struct StcMethod;
+
+impl EmbeddingMethod for StcMethod {
+    fn name(&self) -> &'static str {
+        "stc"
+    }
+
+    fn about(&self) -> &'static str {
+        "Minimal-distortion syndrome-trellis coding with HILL costs (image carriers only)"
+    }
+
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        options: &MethodOptions,
+    ) -> Result<(), Error> {
+        let image = carrier_as_mut::<RgbImage>(carrier, self.name(), "image")?;
+        embed_stc_in_image(
+            hide_content,
+            image,
+            LsbEmbedding::matching(options.key.as_deref()),
+        )
+    }
+
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        _options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error> {
+        let image = carrier_as::<RgbImage>(carrier, self.name(), "image")?;
+        extract_stc_from_image(image)
+    }
+}
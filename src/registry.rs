@@ -0,0 +1,319 @@
+use crate::error::Error;
+use crate::method;
+use crate::traits::{Carrier, ContentType, EmbeddingMethod};
+use crate::utils::audio::PcmAudio;
+use image::RgbImage;
+
+/// A kind of carrier the registry can load, e.g. `image` or `audio`.
+pub struct CarrierFormat {
+    pub name: &'static str,
+    /// Files with this content type's extensions are loaded as this format
+    /// when no format is named.
+    pub content_type: ContentType,
+    pub load: fn(&str) -> Result<Box<dyn Carrier>, Error>,
+}
+
+impl CarrierFormat {
+    pub fn of<C: Carrier + 'static>(name: &'static str, content_type: ContentType) -> Self {
+        CarrierFormat {
+            name,
+            content_type,
+            load: load_boxed::<C>,
+        }
+    }
+}
+
+fn load_boxed<C: Carrier + 'static>(path: &str) -> Result<Box<dyn Carrier>, Error> {
+    Ok(Box::new(C::load(path)?))
+}
+
+/// Carrier formats and embedding methods looked up by name, so that new
+/// ones can be added without changing the code that dispatches to them.
+#[derive(Default)]
+pub struct Registry {
+    carriers: Vec<CarrierFormat>,
+    methods: Vec<Box<dyn EmbeddingMethod>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry holding the `image` and `audio` carrier formats and every
+    /// built-in embedding method.
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register_carrier(CarrierFormat::of::<RgbImage>("image", ContentType::Image));
+        registry.register_carrier(CarrierFormat::of::<PcmAudio>("audio", ContentType::Audio));
+        for method in method::builtin_methods() {
+            registry.register_method(method);
+        }
+        registry
+    }
+
+    /// Adds `format`, replacing any format registered under the same name.
+    pub fn register_carrier(&mut self, format: CarrierFormat) {
+        self.carriers
+            .retain(|existing| existing.name != format.name);
+        self.carriers.push(format);
+    }
+
+    /// Adds `method`, replacing any method registered under the same name.
+    pub fn register_method(&mut self, method: Box<dyn EmbeddingMethod>) {
+        self.methods
+            .retain(|existing| existing.name() != method.name());
+        self.methods.push(method);
+    }
+
+    pub fn carrier(&self, name: &str) -> Option<&CarrierFormat> {
+        self.carriers.iter().find(|format| format.name == name)
+    }
+
+    pub fn method(&self, name: &str) -> Option<&dyn EmbeddingMethod> {
+        self.methods
+            .iter()
+            .find(|method| method.name() == name)
+            .map(|method| method.as_ref())
+    }
+
+    pub fn carriers(&self) -> impl Iterator<Item = &CarrierFormat> {
+        self.carriers.iter()
+    }
+
+    pub fn methods(&self) -> impl Iterator<Item = &dyn EmbeddingMethod> {
+        self.methods.iter().map(|method| method.as_ref())
+    }
+
+    /// The methods tried when extracting without one named, in the order
+    /// they are tried.
+    pub fn probe_methods(&self) -> Vec<&dyn EmbeddingMethod> {
+        let mut methods: Vec<_> = self
+            .methods()
+            .filter(|method| method.probe_order().is_some())
+            .collect();
+        methods.sort_by_key(|method| method.probe_order());
+        methods
+    }
+
+    /// The format a file is loaded as when none is named, chosen by the
+    /// content type its extension implies.
+    pub fn carrier_for_path(&self, path: &str) -> Option<&CarrierFormat> {
+        let content_type = ContentType::from_path(path)?;
+        self.carriers
+            .iter()
+            .find(|format| format.content_type == content_type)
+    }
+
+    /// Looks up the carrier format and method called `carrier` and `method`
+    /// and loads the file at `path` the way the method needs it.
+    pub fn load_carrier(
+        &self,
+        carrier: &str,
+        method: &str,
+        path: &str,
+    ) -> Result<Box<dyn Carrier>, Error> {
+        let format = self.carrier(carrier).ok_or_else(|| {
+            Error::InvalidInput(format!("No carrier format registered for {}", carrier))
+        })?;
+        self.method(method)
+            .ok_or_else(|| Error::InvalidInput(format!("No method registered as {}", method)))?
+            .load_carrier(format, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::carrier::file::CarrierFile;
+    use crate::method::MethodOptions;
+    use crate::testing::{image_bytes, noise_audio, noise_image, secret};
+    use crate::utils::audio::AudioFormat;
+    use image::{imageops, ImageFormat};
+
+    const NAMES: [&str; 15] = [
+        "lsb",
+        "lsb-match",
+        "matrix",
+        "stc",
+        "adaptive",
+        "pvd",
+        "bpcs",
+        "dwt",
+        "deniable",
+        "chunk",
+        "append",
+        "phase",
+        "echo",
+        "spread",
+        "parity",
+    ];
+
+    fn options() -> MethodOptions {
+        MethodOptions {
+            key: Some("key".to_string()),
+            password: Some("password".to_string()),
+            ..MethodOptions::default()
+        }
+        .with("chip-rate", 2048)
+        .with("strength", 0.25)
+    }
+
+    /// A carrier each method can embed eight bytes in.
+    fn carrier_for(method: &str) -> Box<dyn Carrier> {
+        match method {
+            "dwt" => Box::new(imageops::blur(&noise_image(300, 300), 1.5)),
+            "chunk" | "append" => Box::new(CarrierFile {
+                content_type: ContentType::Image,
+                bytes: image_bytes(&noise_image(64, 64), ImageFormat::Png),
+            }),
+            "phase" | "echo" | "spread" | "parity" => Box::new(noise_audio(110 * 2048)),
+            _ => Box::new(noise_image(128, 128)),
+        }
+    }
+
+    #[test]
+    fn defaults_hold_every_builtin() {
+        let registry = Registry::with_defaults();
+        let carriers: Vec<_> = registry.carriers().map(|format| format.name).collect();
+        assert_eq!(carriers, ["image", "audio"]);
+        let methods: Vec<_> = registry.methods().map(|method| method.name()).collect();
+        assert_eq!(methods, NAMES);
+    }
+
+    #[test]
+    fn registering_replaces_by_name() {
+        let mut registry = Registry::with_defaults();
+        registry.register_carrier(CarrierFormat::of::<PcmAudio>("image", ContentType::Audio));
+        registry.register_method(Box::new(method::lsb::LsbMethod { matching: true }));
+        assert_eq!(registry.carriers().count(), 2);
+        assert_eq!(registry.methods().count(), NAMES.len());
+        assert_eq!(
+            registry.carrier("image").unwrap().content_type,
+            ContentType::Audio
+        );
+        assert_eq!(registry.methods().last().unwrap().name(), "lsb-match");
+    }
+
+    #[test]
+    fn picks_the_carrier_by_extension() {
+        let registry = Registry::with_defaults();
+        assert_eq!(registry.carrier_for_path("x.png").unwrap().name, "image");
+        assert_eq!(registry.carrier_for_path("x.wav").unwrap().name, "audio");
+        assert!(registry.carrier_for_path("x.txt").is_none());
+    }
+
+    #[test]
+    fn probes_chunk_append_then_lsb() {
+        let registry = Registry::with_defaults();
+        let probes: Vec<_> = registry
+            .probe_methods()
+            .iter()
+            .map(|method| method.name())
+            .collect();
+        assert_eq!(probes, ["chunk", "append", "lsb"]);
+    }
+
+    #[test]
+    fn checks_options_against_the_method() {
+        let registry = Registry::with_defaults();
+        let method = |name| registry.method(name).unwrap();
+        let zip = MethodOptions::default().with("zip", true);
+        assert!(zip.check(method("append")).is_ok());
+        assert!(matches!(
+            zip.check(method("lsb")),
+            Err(Error::InvalidInput(_))
+        ));
+
+        let decoy = MethodOptions {
+            decoy: Some((secret(4), "decoy".to_string())),
+            ..MethodOptions::default()
+        };
+        assert!(decoy.check(method("deniable")).is_ok());
+        assert!(matches!(
+            decoy.check(method("pvd")),
+            Err(Error::InvalidInput(_))
+        ));
+
+        let mut audio = noise_audio(1000);
+        let bad_region = MethodOptions::default().with("region-size", "four");
+        assert!(matches!(
+            method("parity").embed(&mut audio, &secret(4), &bad_region),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn every_method_round_trips() {
+        let registry = Registry::with_defaults();
+        let options = options();
+        let hidden = secret(8);
+        for method in registry.methods() {
+            let mut carrier = carrier_for(method.name());
+            method.embed(carrier.as_mut(), &hidden, &options).unwrap();
+            let (data, content_type) = method.extract(carrier.as_ref(), &options).unwrap();
+            assert_eq!(data, hidden.data, "{}", method.name());
+            assert_eq!(content_type, ContentType::Image, "{}", method.name());
+        }
+    }
+
+    #[test]
+    fn file_methods_load_the_file_as_is() {
+        let registry = Registry::with_defaults();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("carrier.wav");
+        let bytes = noise_audio(4096).to_bytes(AudioFormat::Wav).unwrap();
+        std::fs::write(&path, &bytes).unwrap();
+        let path = path.to_str().unwrap();
+
+        let mut carrier = registry.load_carrier("audio", "chunk", path).unwrap();
+        let method = registry.method("chunk").unwrap();
+        method
+            .embed(carrier.as_mut(), &secret(8), &options())
+            .unwrap();
+        let (data, _) = method.extract(carrier.as_ref(), &options()).unwrap();
+        assert_eq!(data, secret(8).data);
+
+        assert!(registry.load_carrier("audio", "lsb", path).is_ok());
+        assert!(matches!(
+            registry.load_carrier("video", "lsb", path),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            registry.load_carrier("audio", "nope", path),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_the_wrong_kind_of_carrier_and_reports_capacity() {
+        let registry = Registry::with_defaults();
+        let mut audio = noise_audio(1024);
+        let image = noise_image(16, 16);
+        for name in ["pvd", "dwt", "chunk"] {
+            assert!(matches!(
+                registry
+                    .method(name)
+                    .unwrap()
+                    .embed(&mut audio, &secret(8), &options()),
+                Err(Error::InvalidInput(_))
+            ));
+        }
+        for name in ["phase", "parity", "append"] {
+            assert!(matches!(
+                registry.method(name).unwrap().extract(&image, &options()),
+                Err(Error::InvalidInput(_))
+            ));
+        }
+        let lsb = registry.method("lsb").unwrap();
+        assert_eq!(
+            lsb.capacity(&image, &options()),
+            Some((16 * 16 * 3 - 40) / 8)
+        );
+        assert!(registry
+            .method("pvd")
+            .unwrap()
+            .capacity(&image, &options())
+            .is_none());
+    }
+}
@@ -0,0 +1,74 @@
+// Deterministic carriers and payloads shared by the unit tests.
+
+use crate::content::raw::RawContent;
+use crate::traits::ContentType;
+use crate::utils::audio::PcmAudio;
+use image::{ImageFormat, RgbImage};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use std::io::Cursor;
+
+/// An image of uniform noise, so that every pixel is textured.
+pub fn noise_image(width: u32, height: u32) -> RgbImage {
+    let mut rng = ChaCha20Rng::seed_from_u64(1);
+    let mut pixels = vec![0u8; (width * height * 3) as usize];
+    rng.fill_bytes(&mut pixels);
+    RgbImage::from_raw(width, height, pixels).unwrap()
+}
+
+/// Stereo noise at 44.1 kHz, loud enough for the audio methods to work with.
+pub fn noise_audio(frames: usize) -> PcmAudio {
+    let mut rng = ChaCha20Rng::seed_from_u64(2);
+    let samples = (0..frames * 2)
+        .map(|_| rng.gen_range(-12000..12000))
+        .collect();
+    PcmAudio::new(2, 44100, samples)
+}
+
+/// The audio resampled to `sample_rate` by linear interpolation.
+pub fn resample(audio: &PcmAudio, sample_rate: u32) -> PcmAudio {
+    let channels = audio.channels as usize;
+    let frames = audio.samples.len() / channels;
+    let out_frames = frames * sample_rate as usize / audio.sample_rate as usize;
+    let step = audio.sample_rate as f64 / sample_rate as f64;
+    let mut samples = Vec::with_capacity(out_frames * channels);
+    for frame in 0..out_frames {
+        let position = frame as f64 * step;
+        let i = (position as usize).min(frames - 1);
+        let next = (i + 1).min(frames - 1);
+        let t = position - i as f64;
+        for c in 0..channels {
+            let a = audio.samples[i * channels + c] as f64;
+            let b = audio.samples[next * channels + c] as f64;
+            samples.push((a + (b - a) * t).round() as i16);
+        }
+    }
+    PcmAudio::new(audio.channels, sample_rate, samples)
+}
+
+/// The audio with its samples rounded to `bits` bits.
+pub fn requantize(audio: &PcmAudio, bits: u32) -> PcmAudio {
+    let step = 1i32 << (16 - bits);
+    let samples = audio
+        .samples
+        .iter()
+        .map(|&sample| {
+            let rounded = (sample as i32 + step / 2).div_euclid(step) * step;
+            rounded.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+        })
+        .collect();
+    PcmAudio::new(audio.channels, audio.sample_rate, samples)
+}
+
+/// The image encoded as a `format` file.
+pub fn image_bytes(image: &RgbImage, format: ImageFormat) -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    image.write_to(&mut out, format).unwrap();
+    out.into_inner()
+}
+
+pub fn secret(len: usize) -> RawContent {
+    let mut data = vec![0u8; len];
+    ChaCha20Rng::seed_from_u64(3).fill_bytes(&mut data);
+    RawContent::new(data, ContentType::Image)
+}
@@ -1,3 +1,8 @@
+use crate::error::Error;
+use crate::method::{MethodOptions, Setting};
+use crate::registry::CarrierFormat;
+use std::any::Any;
+
 pub trait Encodable {
     fn to_bytes(&self) -> Vec<u8>;
     fn content_type(&self) -> ContentType;
@@ -5,7 +10,70 @@ pub trait Encodable {
 }
 
 pub trait Decodable: Sized {
-    fn from_bytes(data: &[u8]) -> Result<Self, String>;
+    fn from_bytes(data: &[u8]) -> Result<Self, Error>;
+}
+
+/// A cover medium loaded from a file. Bit-level methods see it as a run of
+/// values that each hold one bit in their LSB; methods written for one kind
+/// of medium downcast it to the concrete carrier type.
+pub trait Carrier: Any {
+    fn load(path: &str) -> Result<Self, Error>
+    where
+        Self: Sized;
+    fn save(&self, path: &str) -> Result<(), Error>;
+    /// Number of values, and so of bits, the carrier holds.
+    fn capacity(&self) -> usize;
+    /// The bit held by every value, in embedding order.
+    fn read_bits(&self) -> Vec<u8>;
+    /// Sets the bits of the values from `start` on to `bits`, wrapping around
+    /// to the first value.
+    fn write_bits(&mut self, start: usize, bits: &[u8]);
+    /// Moves value `index` one step up or down, stepping the other way at the
+    /// bounds of its range. Either way its bit flips.
+    fn step(&mut self, index: usize, up: bool);
+}
+
+/// A way of hiding a payload in a [`Carrier`], selected by name through
+/// [`Registry`](crate::registry::Registry).
+pub trait EmbeddingMethod {
+    fn name(&self) -> &'static str;
+    /// One-line description shown in the command-line help.
+    fn about(&self) -> &'static str;
+    /// Loads the carrier this method works on from a file of the given
+    /// format. Most methods use the format's own loader.
+    fn load_carrier(&self, format: &CarrierFormat, path: &str) -> Result<Box<dyn Carrier>, Error> {
+        (format.load)(path)
+    }
+    /// Bytes of content the method can hide in `carrier`, when it can tell
+    /// without embedding. Used to divide data across several carriers.
+    fn capacity(&self, _carrier: &dyn Carrier, _options: &MethodOptions) -> Option<usize> {
+        None
+    }
+    /// Settings the method reads from [`MethodOptions`], offered as
+    /// command-line options.
+    fn settings(&self) -> &'static [Setting] {
+        &[]
+    }
+    /// Whether [`MethodOptions::decoy`] can be hidden alongside the payload.
+    fn takes_decoy(&self) -> bool {
+        false
+    }
+    /// Position among the methods tried when extracting without one named,
+    /// lowest first, or `None` to never be tried.
+    fn probe_order(&self) -> Option<u32> {
+        None
+    }
+    fn embed(
+        &self,
+        carrier: &mut dyn Carrier,
+        hide_content: &dyn Encodable,
+        options: &MethodOptions,
+    ) -> Result<(), Error>;
+    fn extract(
+        &self,
+        carrier: &dyn Carrier,
+        options: &MethodOptions,
+    ) -> Result<(Vec<u8>, ContentType), Error>;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -49,6 +117,10 @@ impl ContentType {
             || path.ends_with(".mp3")
             || path.ends_with(".flac")
             || path.ends_with(".ogg")
+            || path.ends_with(".aiff")
+            || path.ends_with(".aif")
+            || path.ends_with(".aifc")
+            || path.ends_with(".caf")
         {
             Some(ContentType::Audio)
         } else {
@@ -0,0 +1,362 @@
+// AIFF / AIFF-C reader and writer for 16-bit integer PCM.
+//
+// Only the COMM and SSND chunks are interpreted. Every other chunk is kept as
+// raw bytes so a carrier can be written back in its original container with
+// only the sample data changed.
+
+use crate::error::Error;
+
+const FORM_ID: &[u8; 4] = b"FORM";
+const AIFF_ID: &[u8; 4] = b"AIFF";
+const AIFC_ID: &[u8; 4] = b"AIFC";
+const COMM_ID: &[u8; 4] = b"COMM";
+const SSND_ID: &[u8; 4] = b"SSND";
+const FVER_ID: &[u8; 4] = b"FVER";
+
+/// Custom chunk used to carry hidden payloads.
+pub const STEG_CHUNK_ID: &[u8; 4] = b"STEG";
+
+const AIFC_VERSION_1: u32 = 0xA280_5140;
+const COMPRESSION_NONE: &[u8; 4] = b"NONE";
+const COMPRESSION_SOWT: &[u8; 4] = b"sowt";
+
+struct Chunk {
+    id: [u8; 4],
+    data: Vec<u8>,
+}
+
+pub struct AiffFile {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub is_aifc: bool,
+    little_endian: bool,
+    chunks: Vec<Chunk>,
+}
+
+impl AiffFile {
+    /// Creates an empty AIFF (or AIFF-C when `is_aifc`) description for
+    /// 16-bit big-endian PCM with the given layout.
+    pub fn new(channels: u16, sample_rate: u32, is_aifc: bool) -> Self {
+        let mut comm = Vec::new();
+        comm.extend_from_slice(&channels.to_be_bytes());
+        comm.extend_from_slice(&0u32.to_be_bytes());
+        comm.extend_from_slice(&16u16.to_be_bytes());
+        comm.extend_from_slice(&f64_to_extended(sample_rate as f64));
+
+        let mut chunks = Vec::new();
+        if is_aifc {
+            comm.extend_from_slice(COMPRESSION_NONE);
+            comm.extend_from_slice(&pascal_string(b"not compressed"));
+            chunks.push(Chunk {
+                id: *FVER_ID,
+                data: AIFC_VERSION_1.to_be_bytes().to_vec(),
+            });
+        }
+        chunks.push(Chunk {
+            id: *COMM_ID,
+            data: comm,
+        });
+        chunks.push(Chunk {
+            id: *SSND_ID,
+            data: vec![0u8; 8],
+        });
+
+        Self {
+            channels,
+            sample_rate,
+            is_aifc,
+            little_endian: false,
+            chunks,
+        }
+    }
+
+    /// Serializes the file with `samples` (interleaved) as the sound data.
+    /// All chunks other than COMM and SSND are written back unchanged.
+    pub fn to_bytes(&self, samples: &[i16]) -> Vec<u8> {
+        let frames = samples.len() as u32 / self.channels.max(1) as u32;
+
+        let mut body = Vec::new();
+        body.extend_from_slice(if self.is_aifc { AIFC_ID } else { AIFF_ID });
+
+        for chunk in &self.chunks {
+            let data = match &chunk.id {
+                COMM_ID => {
+                    let mut comm = chunk.data.clone();
+                    comm[2..6].copy_from_slice(&frames.to_be_bytes());
+                    comm
+                }
+                SSND_ID => {
+                    let offset = u32::from_be_bytes(chunk.data[0..4].try_into().unwrap()) as usize;
+                    let mut ssnd = chunk.data[..8 + offset].to_vec();
+                    for &sample in samples {
+                        if self.little_endian {
+                            ssnd.extend_from_slice(&sample.to_le_bytes());
+                        } else {
+                            ssnd.extend_from_slice(&sample.to_be_bytes());
+                        }
+                    }
+                    ssnd
+                }
+                _ => chunk.data.clone(),
+            };
+            write_chunk(&mut body, &chunk.id, &data);
+        }
+
+        let mut out = Vec::with_capacity(body.len() + 8);
+        out.extend_from_slice(FORM_ID);
+        out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        out.extend_from_slice(&body);
+        out
+    }
+}
+
+/// Parses an AIFF or AIFF-C file, returning its description and the
+/// interleaved 16-bit samples.
+pub fn read_aiff(bytes: &[u8]) -> Result<(AiffFile, Vec<i16>), Error> {
+    let (is_aifc, chunks) = parse_form_chunks(bytes)?;
+
+    let comm = chunks
+        .iter()
+        .find(|c| &c.id == COMM_ID)
+        .ok_or_else(|| Error::Format("AIFF file has no COMM chunk".to_string()))?;
+    if comm.data.len() < 18 {
+        return Err(Error::Format(
+            "Corrupted AIFF file: COMM chunk too short".to_string(),
+        ));
+    }
+    let channels = u16::from_be_bytes([comm.data[0], comm.data[1]]);
+    let bits_per_sample = u16::from_be_bytes([comm.data[6], comm.data[7]]);
+    let mut rate_bytes = [0u8; 10];
+    rate_bytes.copy_from_slice(&comm.data[8..18]);
+    let sample_rate = extended_to_f64(&rate_bytes).round() as u32;
+
+    let little_endian = if is_aifc {
+        if comm.data.len() < 22 {
+            return Err(Error::Format(
+                "Corrupted AIFF-C file: COMM chunk has no compression type".to_string(),
+            ));
+        }
+        match &comm.data[18..22] {
+            c if c == COMPRESSION_NONE => false,
+            c if c == COMPRESSION_SOWT => true,
+            c => {
+                return Err(Error::Format(format!(
+                    "Unsupported AIFF-C compression type '{}'. Only uncompressed PCM is supported.",
+                    String::from_utf8_lossy(c)
+                )));
+            }
+        }
+    } else {
+        false
+    };
+
+    if bits_per_sample != 16 {
+        return Err(Error::Format(format!(
+            "AIFF carrier must contain 16-bit PCM samples, found {}-bit",
+            bits_per_sample
+        )));
+    }
+
+    let ssnd = chunks
+        .iter()
+        .find(|c| &c.id == SSND_ID)
+        .ok_or_else(|| Error::Format("AIFF file has no SSND chunk".to_string()))?;
+    if ssnd.data.len() < 8 {
+        return Err(Error::Format(
+            "Corrupted AIFF file: SSND chunk too short".to_string(),
+        ));
+    }
+    let data_offset = 8 + u32::from_be_bytes(ssnd.data[0..4].try_into().unwrap()) as usize;
+    if data_offset > ssnd.data.len() {
+        return Err(Error::Format(
+            "Corrupted AIFF file: SSND offset past end of chunk".to_string(),
+        ));
+    }
+
+    let samples = ssnd.data[data_offset..]
+        .chunks_exact(2)
+        .map(|b| {
+            if little_endian {
+                i16::from_le_bytes([b[0], b[1]])
+            } else {
+                i16::from_be_bytes([b[0], b[1]])
+            }
+        })
+        .collect();
+
+    Ok((
+        AiffFile {
+            channels,
+            sample_rate,
+            is_aifc,
+            little_endian,
+            chunks,
+        },
+        samples,
+    ))
+}
+
+/// Returns a copy of `bytes` with a chunk `id` appended to the FORM body.
+/// Any existing chunks with the same id are dropped; everything else,
+/// including the sound data, is copied byte for byte.
+pub fn insert_aiff_chunk(bytes: &[u8], id: &[u8; 4], data: &[u8]) -> Result<Vec<u8>, Error> {
+    let (is_aifc, mut chunks) = parse_form_chunks(bytes)?;
+    chunks.retain(|c| &c.id != id);
+    chunks.push(Chunk {
+        id: *id,
+        data: data.to_vec(),
+    });
+
+    let mut body = Vec::new();
+    body.extend_from_slice(if is_aifc { AIFC_ID } else { AIFF_ID });
+    for chunk in &chunks {
+        write_chunk(&mut body, &chunk.id, &chunk.data);
+    }
+
+    let mut out = Vec::with_capacity(body.len() + 8);
+    out.extend_from_slice(FORM_ID);
+    out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+pub fn find_aiff_chunk(bytes: &[u8], id: &[u8; 4]) -> Result<Option<Vec<u8>>, Error> {
+    Ok(parse_form_chunks(bytes)?
+        .1
+        .into_iter()
+        .find(|c| &c.id == id)
+        .map(|c| c.data))
+}
+
+fn parse_form_chunks(bytes: &[u8]) -> Result<(bool, Vec<Chunk>), Error> {
+    if bytes.len() < 12 || &bytes[0..4] != FORM_ID {
+        return Err(Error::Format(
+            "Not an AIFF file: missing FORM header".to_string(),
+        ));
+    }
+    let is_aifc = match &bytes[8..12] {
+        id if id == AIFF_ID => false,
+        id if id == AIFC_ID => true,
+        _ => {
+            return Err(Error::Format(
+                "Not an AIFF file: unknown FORM type".to_string(),
+            ))
+        }
+    };
+
+    let form_len = u32::from_be_bytes(bytes[4..8].try_into().unwrap()) as usize;
+    let form_end = (8 + form_len).min(bytes.len());
+
+    let mut chunks = Vec::new();
+    let mut offset = 12;
+    while offset + 8 <= form_end {
+        let mut id = [0u8; 4];
+        id.copy_from_slice(&bytes[offset..offset + 4]);
+        let len = u32::from_be_bytes(bytes[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let start = offset + 8;
+        let end = start + len;
+        if end > bytes.len() {
+            return Err(Error::Format(format!(
+                "Corrupted AIFF file: chunk '{}' extends past end of file",
+                String::from_utf8_lossy(&id)
+            )));
+        }
+        chunks.push(Chunk {
+            id,
+            data: bytes[start..end].to_vec(),
+        });
+        offset = end + (len & 1);
+    }
+    Ok((is_aifc, chunks))
+}
+
+fn write_chunk(out: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+fn pascal_string(s: &[u8]) -> Vec<u8> {
+    let mut out = vec![s.len() as u8];
+    out.extend_from_slice(s);
+    if out.len() % 2 == 1 {
+        out.push(0);
+    }
+    out
+}
+
+// The COMM sample rate is an 80-bit IEEE 754 extended precision float.
+fn extended_to_f64(bytes: &[u8; 10]) -> f64 {
+    let sign = if bytes[0] & 0x80 != 0 { -1.0 } else { 1.0 };
+    let exponent = (((bytes[0] & 0x7F) as i32) << 8) | bytes[1] as i32;
+    let mantissa = u64::from_be_bytes(bytes[2..10].try_into().unwrap());
+    if exponent == 0 && mantissa == 0 {
+        return 0.0;
+    }
+    sign * (mantissa as f64) * 2f64.powi(exponent - 16383 - 63)
+}
+
+fn f64_to_extended(value: f64) -> [u8; 10] {
+    let mut out = [0u8; 10];
+    if value <= 0.0 {
+        return out;
+    }
+    let exponent = value.log2().floor() as i32;
+    let mantissa = (value / 2f64.powi(exponent - 63)) as u64;
+    let biased = (exponent + 16383) as u16;
+    out[0..2].copy_from_slice(&biased.to_be_bytes());
+    out[2..10].copy_from_slice(&mantissa.to_be_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples() -> Vec<i16> {
+        (0..2000)
+            .map(|i| ((i * 37) % 65536 - 32768) as i16)
+            .collect()
+    }
+
+    #[test]
+    fn aiff_and_aifc_round_trip() {
+        for is_aifc in [false, true] {
+            let bytes = AiffFile::new(2, 44100, is_aifc).to_bytes(&samples());
+            let (file, read) = read_aiff(&bytes).unwrap();
+            assert_eq!(file.channels, 2);
+            assert_eq!(file.sample_rate, 44100);
+            assert_eq!(file.is_aifc, is_aifc);
+            assert_eq!(read, samples());
+        }
+    }
+
+    #[test]
+    fn inserted_chunk_survives_rewrite() {
+        let bytes = AiffFile::new(1, 22050, false).to_bytes(&samples());
+        let bytes = insert_aiff_chunk(&bytes, STEG_CHUNK_ID, b"odd").unwrap();
+        assert_eq!(
+            find_aiff_chunk(&bytes, STEG_CHUNK_ID).unwrap().as_deref(),
+            Some(&b"odd"[..])
+        );
+
+        let (file, read) = read_aiff(&bytes).unwrap();
+        assert_eq!(read, samples());
+        let rewritten = file.to_bytes(&read);
+        assert_eq!(
+            find_aiff_chunk(&rewritten, STEG_CHUNK_ID)
+                .unwrap()
+                .as_deref(),
+            Some(&b"odd"[..])
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        let bytes = AiffFile::new(2, 44100, false).to_bytes(&samples());
+        assert!(matches!(read_aiff(&bytes[..10]), Err(Error::Format(_))));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::error::Error;
+use crate::utils::gif::{gif_end, is_gif};
+use crate::utils::jpeg::{is_jpeg, jpeg_end};
+use crate::utils::mp3::{is_mp3, mp3_end};
+use crate::utils::png::{is_png, png_end};
+use crate::utils::wav::{fix_streamed_sizes, wav_end};
+
+// Container written after the carrier's logical end:
+// magic (8 bytes) + version (1 byte) + payload
+const APPEND_MAGIC: &[u8; 8] = b"STEGAPND";
+const APPEND_VERSION: u8 = 1;
+
+const ZIP_LOCAL_HEADER_SIG: u32 = 0x0403_4B50;
+const ZIP_CENTRAL_HEADER_SIG: u32 = 0x0201_4B50;
+const ZIP_END_OF_CENTRAL_DIR_SIG: u32 = 0x0605_4B50;
+const ZIP_ENTRY_NAME: &[u8] = b"hidden.steg";
+const ZIP_VERSION: u16 = 20;
+const ZIP_DOS_DATE: u16 = (1 << 5) | 1; // 1980-01-01
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppendFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Wav,
+    Mp3,
+}
+
+impl AppendFormat {
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if is_png(bytes) {
+            Some(AppendFormat::Png)
+        } else if is_jpeg(bytes) {
+            Some(AppendFormat::Jpeg)
+        } else if is_gif(bytes) {
+            Some(AppendFormat::Gif)
+        } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WAVE") {
+            Some(AppendFormat::Wav)
+        } else if is_mp3(bytes) {
+            Some(AppendFormat::Mp3)
+        } else {
+            None
+        }
+    }
+
+    /// Byte offset where the carrier's own data ends.
+    pub fn logical_end(&self, bytes: &[u8]) -> Result<usize, Error> {
+        match self {
+            AppendFormat::Png => png_end(bytes),
+            AppendFormat::Jpeg => jpeg_end(bytes),
+            AppendFormat::Gif => gif_end(bytes),
+            AppendFormat::Wav => wav_end(bytes),
+            AppendFormat::Mp3 => mp3_end(bytes),
+        }
+    }
+}
+
+/// Returns the carrier truncated at its logical end, with a streamed WAV's
+/// sizes filled in, and the versioned container appended. With `as_zip`,
+/// the container is wrapped in a single-entry ZIP archive so the output also
+/// opens as an archive.
+pub fn append_payload(
+    carrier: &[u8],
+    format: AppendFormat,
+    payload: &[u8],
+    as_zip: bool,
+) -> Result<Vec<u8>, Error> {
+    let end = format.logical_end(carrier)?;
+
+    let mut container = Vec::with_capacity(APPEND_MAGIC.len() + 1 + payload.len());
+    container.extend_from_slice(APPEND_MAGIC);
+    container.push(APPEND_VERSION);
+    container.extend_from_slice(payload);
+
+    let mut out = carrier[..end].to_vec();
+    if format == AppendFormat::Wav {
+        fix_streamed_sizes(&mut out);
+    }
+    if as_zip {
+        write_zip(&mut out, &container)?;
+    } else {
+        out.extend_from_slice(&container);
+    }
+    Ok(out)
+}
+
+/// Locates a container written by [`append_payload`] after the carrier's
+/// logical end and returns its payload.
+pub fn find_appended_payload(bytes: &[u8], format: AppendFormat) -> Result<Option<Vec<u8>>, Error> {
+    let end = format.logical_end(bytes)?;
+    let trailer = &bytes[end..];
+
+    let container = if trailer.starts_with(APPEND_MAGIC) {
+        trailer
+    } else if trailer.len() >= 30 && read_u32(trailer, 0) == ZIP_LOCAL_HEADER_SIG {
+        let compressed_len = read_u32(trailer, 18) as usize;
+        let name_len = read_u16(trailer, 26) as usize;
+        let extra_len = read_u16(trailer, 28) as usize;
+        let data_start = 30 + name_len + extra_len;
+        match trailer.get(data_start..data_start + compressed_len) {
+            Some(data) if data.starts_with(APPEND_MAGIC) => data,
+            _ => return Ok(None),
+        }
+    } else {
+        return Ok(None);
+    };
+
+    let version = *container.get(APPEND_MAGIC.len()).ok_or_else(|| {
+        Error::Corrupt("Corrupted data: appended container has no version".to_string())
+    })?;
+    if version != APPEND_VERSION {
+        return Err(Error::Format(format!(
+            "Unsupported appended container version {} (expected {})",
+            version, APPEND_VERSION
+        )));
+    }
+    Ok(Some(container[APPEND_MAGIC.len() + 1..].to_vec()))
+}
+
+// Writes a single stored (uncompressed) entry ZIP archive. Offsets are
+// absolute, so they account for the carrier bytes already in `out`.
+fn write_zip(out: &mut Vec<u8>, data: &[u8]) -> Result<(), Error> {
+    let len = u32::try_from(data.len()).map_err(|_| {
+        Error::Capacity("Payload too large for a ZIP polyglot (over 4 GiB)".to_string())
+    })?;
+    let crc = crc32fast::hash(data);
+    let local_header_offset = out.len() as u32;
+
+    out.extend_from_slice(&ZIP_LOCAL_HEADER_SIG.to_le_bytes());
+    out.extend_from_slice(&ZIP_VERSION.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // flags
+    out.extend_from_slice(&0u16.to_le_bytes()); // method: stored
+    out.extend_from_slice(&0u16.to_le_bytes()); // time
+    out.extend_from_slice(&ZIP_DOS_DATE.to_le_bytes());
+    out.extend_from_slice(&crc.to_le_bytes());
+    out.extend_from_slice(&len.to_le_bytes()); // compressed size
+    out.extend_from_slice(&len.to_le_bytes()); // uncompressed size
+    out.extend_from_slice(&(ZIP_ENTRY_NAME.len() as u16).to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+    out.extend_from_slice(ZIP_ENTRY_NAME);
+    out.extend_from_slice(data);
+
+    let central_dir_offset = out.len() as u32;
+    out.extend_from_slice(&ZIP_CENTRAL_HEADER_SIG.to_le_bytes());
+    out.extend_from_slice(&ZIP_VERSION.to_le_bytes()); // version made by
+    out.extend_from_slice(&ZIP_VERSION.to_le_bytes()); // version needed
+    out.extend_from_slice(&0u16.to_le_bytes()); // flags
+    out.extend_from_slice(&0u16.to_le_bytes()); // method: stored
+    out.extend_from_slice(&0u16.to_le_bytes()); // time
+    out.extend_from_slice(&ZIP_DOS_DATE.to_le_bytes());
+    out.extend_from_slice(&crc.to_le_bytes());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&(ZIP_ENTRY_NAME.len() as u16).to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+    out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+    out.extend_from_slice(&0u16.to_le_bytes()); // disk number start
+    out.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+    out.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+    out.extend_from_slice(&local_header_offset.to_le_bytes());
+    out.extend_from_slice(ZIP_ENTRY_NAME);
+    let central_dir_len = out.len() as u32 - central_dir_offset;
+
+    out.extend_from_slice(&ZIP_END_OF_CENTRAL_DIR_SIG.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // this disk
+    out.extend_from_slice(&0u16.to_le_bytes()); // disk with central directory
+    out.extend_from_slice(&1u16.to_le_bytes()); // entries on this disk
+    out.extend_from_slice(&1u16.to_le_bytes()); // total entries
+    out.extend_from_slice(&central_dir_len.to_le_bytes());
+    out.extend_from_slice(&central_dir_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+
+    if out.len() > u32::MAX as usize {
+        return Err(Error::Capacity(
+            "Carrier and payload too large for a ZIP polyglot (over 4 GiB)".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zip_polyglot_is_a_valid_archive() {
+        let carrier = b"GIF89a\x01\x00\x01\x00\x00\x00\x00;".to_vec();
+        let out = append_payload(&carrier, AppendFormat::Gif, b"payload", true).unwrap();
+
+        let eocd = out.len() - 22;
+        assert_eq!(read_u32(&out, eocd), ZIP_END_OF_CENTRAL_DIR_SIG);
+        let central_dir = read_u32(&out, eocd + 16) as usize;
+        assert_eq!(read_u32(&out, central_dir), ZIP_CENTRAL_HEADER_SIG);
+        let local_header = read_u32(&out, central_dir + 42) as usize;
+        assert_eq!(local_header, carrier.len());
+
+        let stored = find_appended_payload(&out, AppendFormat::Gif).unwrap();
+        assert_eq!(stored.as_deref(), Some(&b"payload"[..]));
+        let container = [&APPEND_MAGIC[..], &[APPEND_VERSION], b"payload"].concat();
+        assert_eq!(
+            read_u32(&out, local_header + 14),
+            crc32fast::hash(&container)
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use crate::error::Error;
+use crate::utils::aiff::{read_aiff, AiffFile};
+use crate::utils::caf::{read_caf, CafFile};
+use crate::utils::wav::{read_wav, WavFile};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::NamedTempFile; // For managing the temporary WAV file
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AudioFormat {
+    Wav,
+    Aiff,
+    Aifc,
+    Caf,
+}
+
+impl AudioFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "wav" => Some(AudioFormat::Wav),
+            "aiff" | "aif" => Some(AudioFormat::Aiff),
+            "aifc" => Some(AudioFormat::Aifc),
+            "caf" => Some(AudioFormat::Caf),
+            _ => None,
+        }
+    }
+
+    /// Detects the container from the file's magic bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 12 {
+            return None;
+        }
+        match (&bytes[0..4], &bytes[8..12]) {
+            (b"RIFF", b"WAVE") => Some(AudioFormat::Wav),
+            (b"FORM", b"AIFF") => Some(AudioFormat::Aiff),
+            (b"FORM", b"AIFC") => Some(AudioFormat::Aifc),
+            (b"caff", _) => Some(AudioFormat::Caf),
+            _ => None,
+        }
+    }
+}
+
+enum Container {
+    Wav(WavFile),
+    Aiff(AiffFile),
+    Caf(CafFile),
+    /// Samples that were not read from a file.
+    Raw,
+}
+
+/// 16-bit integer PCM audio together with the container it was read from,
+/// so that it can be written back without losing container-level data.
+pub struct PcmAudio {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub samples: Vec<i16>,
+    container: Container,
+}
+
+impl PcmAudio {
+    /// Interleaved samples with no container; written out as a fresh file.
+    pub fn new(channels: u16, sample_rate: u32, samples: Vec<i16>) -> Self {
+        PcmAudio {
+            channels,
+            sample_rate,
+            samples,
+            container: Container::Raw,
+        }
+    }
+
+    /// Parses a WAV, AIFF, AIFF-C or CAF file held in memory.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let format = AudioFormat::from_bytes(bytes).ok_or_else(|| {
+            Error::Format(
+                "Unsupported audio container. Expected WAV, AIFF, AIFF-C or CAF.".to_string(),
+            )
+        })?;
+        Self::parse(format, bytes)
+    }
+
+    fn parse(format: AudioFormat, bytes: &[u8]) -> Result<Self, Error> {
+        Ok(match format {
+            AudioFormat::Wav => {
+                let (wav, samples) = read_wav(bytes)?;
+                PcmAudio {
+                    channels: wav.channels,
+                    sample_rate: wav.sample_rate,
+                    samples,
+                    container: Container::Wav(wav),
+                }
+            }
+            AudioFormat::Aiff | AudioFormat::Aifc => {
+                let (aiff, samples) = read_aiff(bytes)?;
+                PcmAudio {
+                    channels: aiff.channels,
+                    sample_rate: aiff.sample_rate,
+                    samples,
+                    container: Container::Aiff(aiff),
+                }
+            }
+            AudioFormat::Caf => {
+                let (caf, samples) = read_caf(bytes)?;
+                PcmAudio {
+                    channels: caf.channels,
+                    sample_rate: caf.sample_rate,
+                    samples,
+                    container: Container::Caf(caf),
+                }
+            }
+        })
+    }
+
+    /// Serializes the audio as a `format` file. When that matches the
+    /// container the audio was read from, the original container data is
+    /// carried over; otherwise a fresh file is created.
+    pub fn to_bytes(&self, format: AudioFormat) -> Result<Vec<u8>, Error> {
+        Ok(match (format, &self.container) {
+            (AudioFormat::Wav, Container::Wav(wav)) => wav.to_bytes(&self.samples),
+            (AudioFormat::Wav, _) => {
+                WavFile::new(self.channels, self.sample_rate)?.to_bytes(&self.samples)
+            }
+            (AudioFormat::Aiff, Container::Aiff(aiff)) if !aiff.is_aifc => {
+                aiff.to_bytes(&self.samples)
+            }
+            (AudioFormat::Aifc, Container::Aiff(aiff)) if aiff.is_aifc => {
+                aiff.to_bytes(&self.samples)
+            }
+            (AudioFormat::Aiff, _) => {
+                AiffFile::new(self.channels, self.sample_rate, false).to_bytes(&self.samples)
+            }
+            (AudioFormat::Aifc, _) => {
+                AiffFile::new(self.channels, self.sample_rate, true).to_bytes(&self.samples)
+            }
+            (AudioFormat::Caf, Container::Caf(caf)) => caf.to_bytes(&self.samples),
+            (AudioFormat::Caf, _) => {
+                CafFile::new(self.channels, self.sample_rate).to_bytes(&self.samples)
+            }
+        })
+    }
+}
+
+pub fn read_pcm16(path: &Path) -> Result<PcmAudio, Error> {
+    let format = AudioFormat::from_path(path).ok_or_else(|| {
+        Error::Format(format!(
+            "Unsupported audio container for '{}'. Expected WAV, AIFF, AIFF-C or CAF.",
+            path.display()
+        ))
+    })?;
+
+    let bytes = fs::read(path)
+        .map_err(|e| Error::io(format!("Failed to read audio file '{}'", path.display()), e))?;
+    PcmAudio::parse(format, &bytes)
+        .map_err(|e| Error::Format(format!("'{}': {}", path.display(), e)))
+}
+
+/// Loads a carrier as 16-bit PCM. WAV, AIFF and CAF files are read directly;
+/// any other format is first converted to a temporary WAV with ffmpeg.
+pub fn load_carrier_audio(carrier_audio_path_str: &str) -> Result<PcmAudio, Error> {
+    let initial_carrier_path = Path::new(carrier_audio_path_str);
+    let wav_path_for_processing: PathBuf;
+    let mut _temp_wav_holder: Option<NamedTempFile> = None;
+
+    if AudioFormat::from_path(initial_carrier_path).is_some() {
+        wav_path_for_processing = initial_carrier_path.to_path_buf();
+        println!(
+            "Using existing PCM audio file as carrier: {}",
+            carrier_audio_path_str
+        );
+    } else {
+        println!(
+            "Carrier '{}' is not a WAV, AIFF or CAF file. Converting to a temporary WAV...",
+            carrier_audio_path_str
+        );
+
+        let temp_wav = tempfile::Builder::new()
+            .suffix(".wav")
+            .tempfile()
+            .map_err(|e| Error::io("Failed to create temporary file for WAV conversion", e))?;
+        let temp_wav_path = temp_wav.path().to_path_buf();
+
+        let ffmpeg_status = StdCommand::new("ffmpeg")
+            .arg("-y")
+            .arg("-i")
+            .arg(initial_carrier_path)
+            .arg("-vn")
+            .arg("-acodec")
+            .arg("pcm_s16le")
+            .arg(&temp_wav_path)
+            .status()
+            .map_err(|e| {
+                Error::codec(
+                    "Failed to execute ffmpeg. Is it installed and in PATH?",
+                    Some(e),
+                )
+            })?;
+
+        if !ffmpeg_status.success() {
+            let ffmpeg_output = StdCommand::new("ffmpeg")
+                .arg("-y")
+                .arg("-i")
+                .arg(initial_carrier_path)
+                .arg("-vn")
+                .arg("-acodec")
+                .arg("pcm_s16le")
+                .arg(&temp_wav_path)
+                .output()
+                .unwrap();
+
+            return Err(Error::codec(
+                format!(
+                    "ffmpeg failed to convert '{}' to WAV (exit code: {}). Stderr: {}",
+                    carrier_audio_path_str,
+                    ffmpeg_status.code().unwrap_or(-1),
+                    String::from_utf8_lossy(&ffmpeg_output.stderr)
+                ),
+                None,
+            ));
+        }
+        println!(
+            "Successfully converted carrier to temporary WAV: {:?}",
+            temp_wav_path
+        );
+        wav_path_for_processing = temp_wav_path;
+        _temp_wav_holder = Some(temp_wav);
+    }
+
+    // If _temp_wav_holder contains a NamedTempFile, it is dropped when this
+    // function returns, and the temporary file is automatically deleted.
+    read_pcm16(&wav_path_for_processing)
+}
+
+/// Writes `audio` to `path` in the container implied by the path's extension.
+pub fn write_pcm16(path: &Path, audio: &PcmAudio) -> Result<(), Error> {
+    let format = AudioFormat::from_path(path).ok_or_else(|| {
+        Error::InvalidInput(format!(
+            "Output audio path '{}' must have a .wav, .aiff, .aifc or .caf extension.",
+            path.display()
+        ))
+    })?;
+
+    fs::write(path, audio.to_bytes(format)?).map_err(|e| {
+        Error::io(
+            format!("Failed to write output audio '{}'", path.display()),
+            e,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_containers() {
+        let samples: Vec<i16> = (0..1000).map(|i| (i * 61 % 4000 - 2000) as i16).collect();
+        let audio = PcmAudio::new(2, 44100, samples.clone());
+        for format in [
+            AudioFormat::Wav,
+            AudioFormat::Aiff,
+            AudioFormat::Aifc,
+            AudioFormat::Caf,
+        ] {
+            let bytes = audio.to_bytes(format).unwrap();
+            assert_eq!(AudioFormat::from_bytes(&bytes), Some(format));
+            let read = PcmAudio::from_bytes(&bytes).unwrap();
+            assert_eq!(read.channels, 2);
+            assert_eq!(read.sample_rate, 44100);
+            assert_eq!(read.samples, samples);
+        }
+    }
+}
@@ -0,0 +1,270 @@
+// Core Audio Format (CAF) reader and writer for 16-bit linear PCM.
+//
+// Only the `desc` and `data` chunks are interpreted; all other chunks are
+// preserved as raw bytes and written back in their original order.
+
+use crate::error::Error;
+
+const CAF_ID: &[u8; 4] = b"caff";
+const DESC_ID: &[u8; 4] = b"desc";
+const DATA_ID: &[u8; 4] = b"data";
+const LPCM_ID: &[u8; 4] = b"lpcm";
+
+/// Custom chunk used to carry hidden payloads.
+pub const STEG_CHUNK_ID: &[u8; 4] = b"STEG";
+
+const FLAG_IS_FLOAT: u32 = 1 << 0;
+const FLAG_IS_LITTLE_ENDIAN: u32 = 1 << 1;
+
+struct Chunk {
+    id: [u8; 4],
+    data: Vec<u8>,
+}
+
+pub struct CafFile {
+    pub channels: u16,
+    pub sample_rate: u32,
+    little_endian: bool,
+    chunks: Vec<Chunk>,
+}
+
+impl CafFile {
+    /// Creates an empty CAF description for 16-bit little-endian PCM.
+    pub fn new(channels: u16, sample_rate: u32) -> Self {
+        let mut desc = Vec::new();
+        desc.extend_from_slice(&(sample_rate as f64).to_be_bytes());
+        desc.extend_from_slice(LPCM_ID);
+        desc.extend_from_slice(&FLAG_IS_LITTLE_ENDIAN.to_be_bytes());
+        desc.extend_from_slice(&(2 * channels as u32).to_be_bytes()); // bytes per packet
+        desc.extend_from_slice(&1u32.to_be_bytes()); // frames per packet
+        desc.extend_from_slice(&(channels as u32).to_be_bytes());
+        desc.extend_from_slice(&16u32.to_be_bytes());
+
+        Self {
+            channels,
+            sample_rate,
+            little_endian: true,
+            chunks: vec![
+                Chunk {
+                    id: *DESC_ID,
+                    data: desc,
+                },
+                Chunk {
+                    id: *DATA_ID,
+                    data: vec![0u8; 4],
+                },
+            ],
+        }
+    }
+
+    /// Serializes the file with `samples` (interleaved) as the audio data.
+    /// All chunks other than `data` are written back unchanged.
+    pub fn to_bytes(&self, samples: &[i16]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(CAF_ID);
+        out.extend_from_slice(&1u16.to_be_bytes()); // file version
+        out.extend_from_slice(&0u16.to_be_bytes()); // file flags
+
+        for chunk in &self.chunks {
+            let data = if &chunk.id == DATA_ID {
+                // Keep the edit count, replace the sample bytes.
+                let mut data = chunk.data[..4].to_vec();
+                for &sample in samples {
+                    if self.little_endian {
+                        data.extend_from_slice(&sample.to_le_bytes());
+                    } else {
+                        data.extend_from_slice(&sample.to_be_bytes());
+                    }
+                }
+                data
+            } else {
+                chunk.data.clone()
+            };
+            out.extend_from_slice(&chunk.id);
+            out.extend_from_slice(&(data.len() as i64).to_be_bytes());
+            out.extend_from_slice(&data);
+        }
+        out
+    }
+}
+
+/// Parses a CAF file, returning its description and the interleaved 16-bit
+/// samples.
+pub fn read_caf(bytes: &[u8]) -> Result<(CafFile, Vec<i16>), Error> {
+    let chunks = parse_caf_chunks(bytes)?;
+
+    let desc = chunks
+        .iter()
+        .find(|c| &c.id == DESC_ID)
+        .ok_or_else(|| Error::Format("CAF file has no 'desc' chunk".to_string()))?;
+    if desc.data.len() < 32 {
+        return Err(Error::Format(
+            "Corrupted CAF file: 'desc' chunk too short".to_string(),
+        ));
+    }
+    let be_u32 = |at: usize| u32::from_be_bytes(desc.data[at..at + 4].try_into().unwrap());
+    let sample_rate = f64::from_be_bytes(desc.data[0..8].try_into().unwrap()).round() as u32;
+    let format_flags = be_u32(12);
+    let channels = u16::try_from(be_u32(24)).map_err(|_| {
+        Error::Format(format!(
+            "CAF carrier has too many channels ({})",
+            be_u32(24)
+        ))
+    })?;
+    let bits_per_channel = be_u32(28);
+
+    if &desc.data[8..12] != LPCM_ID || format_flags & FLAG_IS_FLOAT != 0 || bits_per_channel != 16 {
+        return Err(Error::Format(
+            "CAF carrier must contain 16-bit integer linear PCM samples".to_string(),
+        ));
+    }
+    let little_endian = format_flags & FLAG_IS_LITTLE_ENDIAN != 0;
+
+    let data = chunks
+        .iter()
+        .find(|c| &c.id == DATA_ID)
+        .ok_or_else(|| Error::Format("CAF file has no 'data' chunk".to_string()))?;
+    if data.data.len() < 4 {
+        return Err(Error::Format(
+            "Corrupted CAF file: 'data' chunk too short".to_string(),
+        ));
+    }
+
+    let samples = data.data[4..]
+        .chunks_exact(2)
+        .map(|b| {
+            if little_endian {
+                i16::from_le_bytes([b[0], b[1]])
+            } else {
+                i16::from_be_bytes([b[0], b[1]])
+            }
+        })
+        .collect();
+
+    Ok((
+        CafFile {
+            channels,
+            sample_rate,
+            little_endian,
+            chunks,
+        },
+        samples,
+    ))
+}
+
+/// Returns a copy of `bytes` with a chunk `id` inserted before the `data`
+/// chunk (which may run to the end of the file). Any existing chunks with the
+/// same id are dropped; everything else is copied byte for byte.
+pub fn insert_caf_chunk(bytes: &[u8], id: &[u8; 4], data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut chunks = parse_caf_chunks(bytes)?;
+    chunks.retain(|c| &c.id != id);
+    let data_index = chunks
+        .iter()
+        .position(|c| &c.id == DATA_ID)
+        .unwrap_or(chunks.len());
+    chunks.insert(
+        data_index,
+        Chunk {
+            id: *id,
+            data: data.to_vec(),
+        },
+    );
+
+    let mut out = bytes[..8].to_vec();
+    for chunk in &chunks {
+        out.extend_from_slice(&chunk.id);
+        out.extend_from_slice(&(chunk.data.len() as i64).to_be_bytes());
+        out.extend_from_slice(&chunk.data);
+    }
+    Ok(out)
+}
+
+pub fn find_caf_chunk(bytes: &[u8], id: &[u8; 4]) -> Result<Option<Vec<u8>>, Error> {
+    Ok(parse_caf_chunks(bytes)?
+        .into_iter()
+        .find(|c| &c.id == id)
+        .map(|c| c.data))
+}
+
+fn parse_caf_chunks(bytes: &[u8]) -> Result<Vec<Chunk>, Error> {
+    if bytes.len() < 8 || &bytes[0..4] != CAF_ID {
+        return Err(Error::Format(
+            "Not a CAF file: missing 'caff' header".to_string(),
+        ));
+    }
+
+    let mut chunks = Vec::new();
+    let mut offset = 8;
+    while offset + 12 <= bytes.len() {
+        let mut id = [0u8; 4];
+        id.copy_from_slice(&bytes[offset..offset + 4]);
+        let len = i64::from_be_bytes(bytes[offset + 4..offset + 12].try_into().unwrap());
+        let start = offset + 12;
+        // A data chunk size of -1 means the chunk runs to the end of the file.
+        let end = if len < 0 {
+            bytes.len()
+        } else {
+            start + len as usize
+        };
+        if end > bytes.len() {
+            return Err(Error::Format(format!(
+                "Corrupted CAF file: chunk '{}' extends past end of file",
+                String::from_utf8_lossy(&id)
+            )));
+        }
+        chunks.push(Chunk {
+            id,
+            data: bytes[start..end].to_vec(),
+        });
+        offset = end;
+    }
+    Ok(chunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples() -> Vec<i16> {
+        (0..2000)
+            .map(|i| ((i * 37) % 65536 - 32768) as i16)
+            .collect()
+    }
+
+    #[test]
+    fn caf_round_trip() {
+        let bytes = CafFile::new(2, 48000).to_bytes(&samples());
+        let (file, read) = read_caf(&bytes).unwrap();
+        assert_eq!(file.channels, 2);
+        assert_eq!(file.sample_rate, 48000);
+        assert_eq!(read, samples());
+    }
+
+    #[test]
+    fn inserted_chunk_survives_rewrite() {
+        let bytes = CafFile::new(1, 44100).to_bytes(&samples());
+        let bytes = insert_caf_chunk(&bytes, STEG_CHUNK_ID, b"hidden").unwrap();
+        assert_eq!(
+            find_caf_chunk(&bytes, STEG_CHUNK_ID).unwrap().as_deref(),
+            Some(&b"hidden"[..])
+        );
+
+        let (file, read) = read_caf(&bytes).unwrap();
+        assert_eq!(read, samples());
+        let rewritten = file.to_bytes(&read);
+        assert_eq!(
+            find_caf_chunk(&rewritten, STEG_CHUNK_ID)
+                .unwrap()
+                .as_deref(),
+            Some(&b"hidden"[..])
+        );
+    }
+
+    #[test]
+    fn rejects_too_many_channels() {
+        let mut bytes = CafFile::new(2, 44100).to_bytes(&samples());
+        // File header (8), desc chunk header (12), then channels at 24.
+        bytes[44..48].copy_from_slice(&70000u32.to_be_bytes());
+        assert!(matches!(read_caf(&bytes), Err(Error::Format(_))));
+    }
+}
@@ -0,0 +1,167 @@
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::sync::Arc;
+
+/// Forward and inverse FFTs of a fixed length for real-valued signals.
+pub struct RealFft {
+    forward: Arc<dyn Fft<f64>>,
+    inverse: Arc<dyn Fft<f64>>,
+    len: usize,
+}
+
+impl RealFft {
+    pub fn new(len: usize) -> Self {
+        let mut planner = FftPlanner::new();
+        Self {
+            forward: planner.plan_fft_forward(len),
+            inverse: planner.plan_fft_inverse(len),
+            len,
+        }
+    }
+
+    pub fn forward(&self, signal: &[f64]) -> Vec<Complex<f64>> {
+        let mut buffer: Vec<Complex<f64>> = signal.iter().map(|&x| Complex::new(x, 0.0)).collect();
+        buffer.resize(self.len, Complex::new(0.0, 0.0));
+        self.forward.process(&mut buffer);
+        buffer
+    }
+
+    /// Inverse transform, normalized so that `inverse(forward(x)) == x`.
+    /// Only the real part is returned; callers are expected to keep the
+    /// spectrum Hermitian-symmetric.
+    pub fn inverse(&self, mut spectrum: Vec<Complex<f64>>) -> Vec<f64> {
+        self.inverse.process(&mut spectrum);
+        let scale = 1.0 / self.len as f64;
+        spectrum.iter().map(|c| c.re * scale).collect()
+    }
+}
+
+/// Extracts one channel from interleaved samples.
+pub fn channel_samples(samples: &[i16], channels: u16, channel: usize) -> Vec<f64> {
+    samples
+        .iter()
+        .skip(channel)
+        .step_by(channels.max(1) as usize)
+        .map(|&s| s as f64)
+        .collect()
+}
+
+/// Writes `signal` back into one channel of interleaved samples, rounding and
+/// clamping to the i16 range.
+pub fn set_channel_samples(samples: &mut [i16], channels: u16, channel: usize, signal: &[f64]) {
+    for (sample, &value) in samples
+        .iter_mut()
+        .skip(channel)
+        .step_by(channels.max(1) as usize)
+        .zip(signal)
+    {
+        *sample = value.round().clamp(i16::MIN as f64, i16::MAX as f64) as i16;
+    }
+}
+
+/// Averages all channels of interleaved samples into a single signal.
+pub fn mono_mix(samples: &[i16], channels: u16) -> Vec<f64> {
+    let channels = channels.max(1) as usize;
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().map(|&s| s as f64).sum::<f64>() / channels as f64)
+        .collect()
+}
+
+/// One level of the orthonormal 2-D Haar transform, applied in place to the
+/// top-left `size` x `size` region of a row-major plane `width` values wide.
+/// The result is laid out as LL | HL over LH | HH.
+pub fn haar_forward_2d(plane: &mut [f64], width: usize, size: usize) {
+    let half = size / 2;
+    let mut line = vec![0.0; size];
+    for y in 0..size {
+        for x in 0..half {
+            let (a, b) = (plane[y * width + 2 * x], plane[y * width + 2 * x + 1]);
+            line[x] = (a + b) / std::f64::consts::SQRT_2;
+            line[half + x] = (a - b) / std::f64::consts::SQRT_2;
+        }
+        plane[y * width..y * width + size].copy_from_slice(&line);
+    }
+    for x in 0..size {
+        for y in 0..half {
+            let (a, b) = (plane[2 * y * width + x], plane[(2 * y + 1) * width + x]);
+            line[y] = (a + b) / std::f64::consts::SQRT_2;
+            line[half + y] = (a - b) / std::f64::consts::SQRT_2;
+        }
+        for (y, &value) in line.iter().enumerate() {
+            plane[y * width + x] = value;
+        }
+    }
+}
+
+/// Inverse of [`haar_forward_2d`].
+pub fn haar_inverse_2d(plane: &mut [f64], width: usize, size: usize) {
+    let half = size / 2;
+    let mut line = vec![0.0; size];
+    for x in 0..size {
+        for y in 0..half {
+            let (low, high) = (plane[y * width + x], plane[(half + y) * width + x]);
+            line[2 * y] = (low + high) / std::f64::consts::SQRT_2;
+            line[2 * y + 1] = (low - high) / std::f64::consts::SQRT_2;
+        }
+        for (y, &value) in line.iter().enumerate() {
+            plane[y * width + x] = value;
+        }
+    }
+    for y in 0..size {
+        for x in 0..half {
+            let (low, high) = (plane[y * width + x], plane[y * width + half + x]);
+            line[2 * x] = (low + high) / std::f64::consts::SQRT_2;
+            line[2 * x + 1] = (low - high) / std::f64::consts::SQRT_2;
+        }
+        plane[y * width..y * width + size].copy_from_slice(&line);
+    }
+}
+
+/// Resizes a row-major plane with a separable triangle (bilinear) filter,
+/// widened when shrinking so every source value contributes. Unlike
+/// `image::imageops::resize`, values are not clamped to a pixel range.
+pub fn resize_plane(
+    plane: &[f64],
+    width: usize,
+    height: usize,
+    new_width: usize,
+    new_height: usize,
+) -> Vec<f64> {
+    let horizontal = resample_weights(width, new_width);
+    let vertical = resample_weights(height, new_height);
+
+    let mut rows = vec![0.0; new_width * height];
+    for y in 0..height {
+        for (x, taps) in horizontal.iter().enumerate() {
+            rows[y * new_width + x] = taps.iter().map(|&(i, w)| w * plane[y * width + i]).sum();
+        }
+    }
+
+    let mut out = vec![0.0; new_width * new_height];
+    for (y, taps) in vertical.iter().enumerate() {
+        for x in 0..new_width {
+            out[y * new_width + x] = taps.iter().map(|&(i, w)| w * rows[i * new_width + x]).sum();
+        }
+    }
+    out
+}
+
+// Normalized (source index, weight) taps for each output position.
+fn resample_weights(len: usize, new_len: usize) -> Vec<Vec<(usize, f64)>> {
+    let ratio = len as f64 / new_len as f64;
+    let support = ratio.max(1.0);
+    (0..new_len)
+        .map(|i| {
+            let center = (i as f64 + 0.5) * ratio - 0.5;
+            let first = (center - support).floor().max(0.0) as usize;
+            let last = ((center + support).ceil() as usize).min(len - 1);
+            let taps: Vec<(usize, f64)> = (first..=last)
+                .map(|j| (j, (1.0 - (j as f64 - center).abs() / support).max(0.0)))
+                .filter(|&(_, w)| w > 0.0)
+                .collect();
+            let total: f64 = taps.iter().map(|&(_, w)| w).sum();
+            taps.into_iter().map(|(j, w)| (j, w / total)).collect()
+        })
+        .collect()
+}
@@ -0,0 +1,66 @@
+use crate::error::Error;
+
+const EXTENSION_INTRODUCER: u8 = 0x21;
+const IMAGE_SEPARATOR: u8 = 0x2C;
+const TRAILER: u8 = 0x3B;
+
+pub fn is_gif(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a")
+}
+
+/// Byte offset just past the GIF trailer, i.e. the logical end of the image.
+pub fn gif_end(bytes: &[u8]) -> Result<usize, Error> {
+    if !is_gif(bytes) || bytes.len() < 13 {
+        return Err(Error::Format("Not a GIF file: missing header".to_string()));
+    }
+
+    // Header (6) + logical screen descriptor (7) + optional global color table
+    let mut offset = 13 + color_table_len(bytes[10]);
+
+    loop {
+        let block = *bytes
+            .get(offset)
+            .ok_or_else(|| Error::Format("Corrupted GIF file: no trailer".to_string()))?;
+        match block {
+            TRAILER => return Ok(offset + 1),
+            EXTENSION_INTRODUCER => {
+                // Introducer + label, then data sub-blocks
+                offset = skip_sub_blocks(bytes, offset + 2)?;
+            }
+            IMAGE_SEPARATOR => {
+                let packed = *bytes.get(offset + 9).ok_or_else(|| {
+                    Error::Format("Corrupted GIF file: truncated image descriptor".to_string())
+                })?;
+                // Descriptor (10) + local color table + LZW minimum code size (1)
+                offset += 10 + color_table_len(packed) + 1;
+                offset = skip_sub_blocks(bytes, offset)?;
+            }
+            _ => {
+                return Err(Error::Format(format!(
+                    "Corrupted GIF file: unexpected block {:#04x} at offset {}",
+                    block, offset
+                )));
+            }
+        }
+    }
+}
+
+fn color_table_len(packed: u8) -> usize {
+    if packed & 0x80 != 0 {
+        3 * (1 << ((packed & 0x07) + 1))
+    } else {
+        0
+    }
+}
+
+fn skip_sub_blocks(bytes: &[u8], mut offset: usize) -> Result<usize, Error> {
+    loop {
+        let size = *bytes.get(offset).ok_or_else(|| {
+            Error::Format("Corrupted GIF file: truncated data sub-block".to_string())
+        })? as usize;
+        offset += 1 + size;
+        if size == 0 {
+            return Ok(offset);
+        }
+    }
+}
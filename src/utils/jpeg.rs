@@ -0,0 +1,221 @@
+use crate::error::Error;
+
+const SOI: u8 = 0xD8;
+const EOI: u8 = 0xD9;
+const SOS: u8 = 0xDA;
+const RST0: u8 = 0xD0;
+const RST7: u8 = 0xD7;
+const APP0: u8 = 0xE0;
+const APP15: u8 = 0xEF;
+
+const MAX_SEGMENT_LEN: usize = 0xFFFF;
+
+/// Identifier prefixing APP15 segments that carry hidden payloads.
+pub const STEG_APP_IDENTIFIER: &[u8] = b"STEG\0";
+
+pub struct JpegSegment<'a> {
+    pub marker: u8,
+    /// Segment payload, excluding the marker and length field.
+    pub data: &'a [u8],
+    /// Byte offset of the segment's 0xFF marker prefix within the file.
+    pub offset: usize,
+}
+
+impl JpegSegment<'_> {
+    pub fn end(&self) -> usize {
+        self.offset + 4 + self.data.len()
+    }
+}
+
+pub fn is_jpeg(bytes: &[u8]) -> bool {
+    bytes.len() >= 3 && bytes[0] == 0xFF && bytes[1] == SOI && bytes[2] == 0xFF
+}
+
+/// Parses the marker segments of a JPEG file's header, up to (but not
+/// including) the first SOS segment. Returns the segments and the offset
+/// of the SOS marker.
+pub fn parse_jpeg_header(bytes: &[u8]) -> Result<(Vec<JpegSegment<'_>>, usize), Error> {
+    if !is_jpeg(bytes) {
+        return Err(Error::Format(
+            "Not a JPEG file: missing SOI marker".to_string(),
+        ));
+    }
+
+    let mut segments = Vec::new();
+    let mut offset = 2;
+    loop {
+        if offset + 4 > bytes.len() || bytes[offset] != 0xFF {
+            return Err(Error::Format(format!(
+                "Corrupted JPEG file: expected marker at offset {}",
+                offset
+            )));
+        }
+        let marker = bytes[offset + 1];
+        if marker == 0xFF {
+            // Fill byte before a marker.
+            offset += 1;
+            continue;
+        }
+        if marker == SOS {
+            return Ok((segments, offset));
+        }
+        let len = u16::from_be_bytes([bytes[offset + 2], bytes[offset + 3]]) as usize;
+        if len < 2 || offset + 2 + len > bytes.len() {
+            return Err(Error::Format(format!(
+                "Corrupted JPEG file: segment {:#04x} at offset {} has invalid length",
+                marker, offset
+            )));
+        }
+        let segment = JpegSegment {
+            marker,
+            data: &bytes[offset + 4..offset + 2 + len],
+            offset,
+        };
+        offset = segment.end();
+        segments.push(segment);
+    }
+}
+
+/// Byte offset just past the EOI marker, i.e. the logical end of the image.
+/// Walks every scan's entropy-coded data, so progressive files with several
+/// SOS segments are handled.
+pub fn jpeg_end(bytes: &[u8]) -> Result<usize, Error> {
+    let (_, mut offset) = parse_jpeg_header(bytes)?;
+
+    while offset + 1 < bytes.len() {
+        if bytes[offset] != 0xFF {
+            return Err(Error::Format(format!(
+                "Corrupted JPEG file: expected marker at offset {}",
+                offset
+            )));
+        }
+        let marker = bytes[offset + 1];
+        match marker {
+            EOI => return Ok(offset + 2),
+            0xFF => {
+                offset += 1;
+                continue;
+            }
+            RST0..=RST7 => {
+                offset += 2;
+                continue;
+            }
+            _ => {}
+        }
+        if offset + 4 > bytes.len() {
+            break;
+        }
+        let len = u16::from_be_bytes([bytes[offset + 2], bytes[offset + 3]]) as usize;
+        offset += 2 + len;
+
+        if marker == SOS {
+            // Skip entropy-coded data up to the next real marker.
+            while offset + 1 < bytes.len() {
+                if bytes[offset] == 0xFF {
+                    let next = bytes[offset + 1];
+                    if next != 0x00 && !(RST0..=RST7).contains(&next) && next != 0xFF {
+                        break;
+                    }
+                }
+                offset += 1;
+            }
+        }
+    }
+    Err(Error::Format(
+        "Corrupted JPEG file: no EOI marker".to_string(),
+    ))
+}
+
+/// Returns a copy of `bytes` with `data` stored in APP15 segments tagged with
+/// `identifier`, placed after the file's existing APPn segments. Existing
+/// APP15 segments with the same identifier are dropped.
+pub fn insert_jpeg_app_segments(
+    bytes: &[u8],
+    identifier: &[u8],
+    data: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let (segments, _) = parse_jpeg_header(bytes)?;
+
+    // identifier + sequence number (u16) + segment count (u16)
+    let max_piece_len = MAX_SEGMENT_LEN - 2 - identifier.len() - 4;
+    let pieces: Vec<&[u8]> = if data.is_empty() {
+        vec![data]
+    } else {
+        data.chunks(max_piece_len).collect()
+    };
+    if pieces.len() > u16::MAX as usize {
+        return Err(Error::Capacity(format!(
+            "Payload of {} bytes is too large to store in JPEG APP segments",
+            data.len()
+        )));
+    }
+
+    let mut new_segments = Vec::new();
+    for (seq, piece) in pieces.iter().enumerate() {
+        let len = 2 + identifier.len() + 4 + piece.len();
+        new_segments.extend_from_slice(&[0xFF, APP15]);
+        new_segments.extend_from_slice(&(len as u16).to_be_bytes());
+        new_segments.extend_from_slice(identifier);
+        new_segments.extend_from_slice(&(seq as u16).to_be_bytes());
+        new_segments.extend_from_slice(&(pieces.len() as u16).to_be_bytes());
+        new_segments.extend_from_slice(piece);
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() + new_segments.len());
+    out.extend_from_slice(&bytes[..2]);
+    let mut inserted = false;
+    let mut copied_until = 2;
+    for segment in &segments {
+        let is_app = (APP0..=APP15).contains(&segment.marker);
+        if !is_app && !inserted {
+            out.extend_from_slice(&new_segments);
+            inserted = true;
+        }
+        if segment.marker != APP15 || !segment.data.starts_with(identifier) {
+            out.extend_from_slice(&bytes[segment.offset..segment.end()]);
+        }
+        copied_until = segment.end();
+    }
+    if !inserted {
+        out.extend_from_slice(&new_segments);
+    }
+    out.extend_from_slice(&bytes[copied_until..]);
+    Ok(out)
+}
+
+/// Collects and reassembles data stored by [`insert_jpeg_app_segments`].
+pub fn find_jpeg_app_segments(bytes: &[u8], identifier: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+    let (segments, _) = parse_jpeg_header(bytes)?;
+
+    let mut pieces: Vec<(u16, u16, &[u8])> = segments
+        .iter()
+        .filter(|s| s.marker == APP15 && s.data.len() >= identifier.len() + 4)
+        .filter(|s| s.data.starts_with(identifier))
+        .map(|s| {
+            let rest = &s.data[identifier.len()..];
+            let seq = u16::from_be_bytes([rest[0], rest[1]]);
+            let total = u16::from_be_bytes([rest[2], rest[3]]);
+            (seq, total, &rest[4..])
+        })
+        .collect();
+
+    if pieces.is_empty() {
+        return Ok(None);
+    }
+    pieces.sort_by_key(|&(seq, _, _)| seq);
+
+    let total = pieces[0].1 as usize;
+    if pieces.len() != total || pieces.iter().enumerate().any(|(i, p)| p.0 as usize != i) {
+        return Err(Error::Format(format!(
+            "Corrupted data: expected {} APP15 segments, found {}",
+            total,
+            pieces.len()
+        )));
+    }
+    Ok(Some(
+        pieces
+            .into_iter()
+            .flat_map(|p| p.2.iter().copied())
+            .collect(),
+    ))
+}
@@ -0,0 +1,55 @@
+use crate::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+pub const KEY_LEN: usize = 32;
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Parses exactly 64 hex digits into a 32-byte key.
+pub fn decode_key_hex(hex: &str) -> Option<[u8; KEY_LEN]> {
+    if hex.len() != KEY_LEN * 2 || !hex.is_ascii() {
+        return None;
+    }
+    let mut key = [0u8; KEY_LEN];
+    for (byte, digits) in key.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?;
+    }
+    Some(key)
+}
+
+/// Reads the secret key from a key file: the first line that is neither
+/// blank nor a `#` comment, as hex.
+pub fn read_secret_key(path: &str) -> Result<[u8; KEY_LEN], Error> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| Error::io(format!("Failed to read key file '{}'", path), e))?;
+    let line = contents
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .ok_or_else(|| Error::Format(format!("No secret key found in key file '{}'", path)))?;
+    decode_key_hex(line)
+        .ok_or_else(|| Error::Format(format!("Malformed secret key in key file '{}'", path)))
+}
+
+/// Writes a new key file readable only by its owner, refusing to overwrite an
+/// existing file. Each comment is written on its own `#` line before the key.
+pub fn write_secret_key(path: &str, comments: &[String], secret: &[u8]) -> Result<(), Error> {
+    let mut contents: String = comments
+        .iter()
+        .map(|comment| format!("# {}\n", comment))
+        .collect();
+    contents.push_str(&encode_hex(secret));
+    contents.push('\n');
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|e| Error::io(format!("Failed to write key file '{}'", path), e))
+}
@@ -1,13 +1,20 @@
-use image::{imageops::FilterType, DynamicImage, GenericImageView};
+use crate::error::Error;
+use image::{imageops::FilterType, DynamicImage, GenericImageView, RgbImage};
 
 const MAX_HEIGHT: u32 = 720;
 
-pub fn load_image(path: &str) -> Result<DynamicImage, String> {
-    image::open(path).map_err(|e| format!("Failed to open image '{}': {}", path, e))
+pub fn load_image(path: &str) -> Result<DynamicImage, Error> {
+    image::open(path).map_err(|e| Error::image(format!("Failed to open image '{}'", path), e))
 }
 
-pub fn load_image_and_resize(path: &str) -> DynamicImage {
-    let img = load_image(path).expect("Failed to open image");
+pub fn save_image(image: &RgbImage, path: &str) -> Result<(), Error> {
+    image
+        .save(path)
+        .map_err(|e| Error::image(format!("Failed to save output image '{}'", path), e))
+}
+
+pub fn load_image_and_resize(path: &str) -> Result<DynamicImage, Error> {
+    let img = load_image(path)?;
     let (width, height) = img.dimensions();
 
     let (new_width, new_height) = if height > MAX_HEIGHT {
@@ -16,9 +23,9 @@ pub fn load_image_and_resize(path: &str) -> DynamicImage {
         (width, height)
     };
 
-    if new_width != width || new_height != height {
+    Ok(if new_width != width || new_height != height {
         img.resize(new_width, new_height, FilterType::Lanczos3)
     } else {
         img
-    }
+    })
 }
@@ -1 +1,18 @@
+pub mod aiff;
+pub mod append;
+pub mod audio;
+pub mod caf;
+pub mod dsp;
+pub mod gif;
+pub mod jpeg;
+pub mod keyfile;
 pub mod load;
+pub mod mp3;
+pub mod password;
+pub mod png;
+pub mod prng;
+pub mod seal;
+pub mod shamir;
+pub mod sign;
+pub mod stripe;
+pub mod wav;
@@ -0,0 +1,109 @@
+use crate::error::Error;
+
+// Bitrates in kbit/s indexed by [MPEG-1 / MPEG-2(.5)][layer - 1][index].
+const BITRATES: [[[u32; 16]; 3]; 2] = [
+    [
+        [
+            0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448, 0,
+        ],
+        [
+            0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384, 0,
+        ],
+        [
+            0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 0,
+        ],
+    ],
+    [
+        [
+            0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256, 0,
+        ],
+        [
+            0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160, 0,
+        ],
+        [
+            0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160, 0,
+        ],
+    ],
+];
+const SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+const ID3V1_LEN: usize = 128;
+
+pub fn is_mp3(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"ID3") || frame_len(bytes, 0).is_some()
+}
+
+/// Byte offset just past the last MPEG audio frame (and a trailing ID3v1 tag,
+/// if present), i.e. the logical end of the stream.
+pub fn mp3_end(bytes: &[u8]) -> Result<usize, Error> {
+    let mut offset = 0;
+
+    if bytes.starts_with(b"ID3") {
+        if bytes.len() < 10 {
+            return Err(Error::Format(
+                "Corrupted MP3 file: truncated ID3v2 header".to_string(),
+            ));
+        }
+        // Tag size is a 28-bit syncsafe integer, excluding the 10-byte header.
+        let size = bytes[6..10]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 7) | (b & 0x7F) as usize);
+        let has_footer = bytes[5] & 0x10 != 0;
+        offset = 10 + size + if has_footer { 10 } else { 0 };
+    }
+
+    let first_frame = offset;
+    while let Some(len) = frame_len(bytes, offset) {
+        if offset + len > bytes.len() {
+            break;
+        }
+        offset += len;
+    }
+    if offset == first_frame {
+        return Err(Error::Format(
+            "Not an MP3 file: no MPEG audio frames found".to_string(),
+        ));
+    }
+
+    if bytes.len() >= offset + ID3V1_LEN && bytes[offset..].starts_with(b"TAG") {
+        offset += ID3V1_LEN;
+    }
+    Ok(offset)
+}
+
+fn frame_len(bytes: &[u8], offset: usize) -> Option<usize> {
+    let header = bytes.get(offset..offset + 4)?;
+    if header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+
+    let version_bits = (header[1] >> 3) & 0x03; // 00 = 2.5, 10 = 2, 11 = 1
+    let layer_bits = (header[1] >> 1) & 0x03; // 01 = III, 10 = II, 11 = I
+    let bitrate_index = (header[2] >> 4) as usize;
+    let sample_rate_index = ((header[2] >> 2) & 0x03) as usize;
+    let padding = ((header[2] >> 1) & 0x01) as u32;
+
+    if version_bits == 0b01 || layer_bits == 0 || sample_rate_index == 3 {
+        return None;
+    }
+    let layer = 4 - layer_bits as usize;
+    let is_mpeg1 = version_bits == 0b11;
+
+    let bitrate = BITRATES[if is_mpeg1 { 0 } else { 1 }][layer - 1][bitrate_index] * 1000;
+    if bitrate == 0 {
+        return None;
+    }
+    let sample_rate = SAMPLE_RATES[sample_rate_index]
+        >> match version_bits {
+            0b11 => 0,
+            0b10 => 1,
+            _ => 2,
+        };
+
+    let len = match layer {
+        1 => (12 * bitrate / sample_rate + padding) * 4,
+        3 if !is_mpeg1 => 72 * bitrate / sample_rate + padding,
+        _ => 144 * bitrate / sample_rate + padding,
+    };
+    Some(len as usize)
+}
@@ -0,0 +1,26 @@
+use crate::error::Error;
+use argon2::Argon2;
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+pub const SALT_LEN: usize = 16;
+pub const KEY_LEN: usize = 32;
+
+/// Stretches `password` into a master key with Argon2id at its default cost.
+pub fn derive_master_key(password: &str, salt: &[u8; SALT_LEN]) -> Result<[u8; KEY_LEN], Error> {
+    let mut key = [0u8; KEY_LEN];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| Error::Crypto(format!("Failed to derive a key from the password: {}", e)))?;
+    Ok(key)
+}
+
+/// Derives an independent subkey for one use of a master key.
+pub fn derive_subkey(master_key: &[u8; KEY_LEN], purpose: &str) -> [u8; KEY_LEN] {
+    let mut subkey = [0u8; KEY_LEN];
+    Hkdf::<Sha256>::from_prk(master_key)
+        .expect("a 32-byte master key is a valid HKDF-SHA256 PRK")
+        .expand(purpose.as_bytes(), &mut subkey)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    subkey
+}
@@ -0,0 +1,105 @@
+use crate::error::Error;
+
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+const IEND_ID: &[u8; 4] = b"IEND";
+
+/// Private, ancillary, safe-to-copy chunk used to carry hidden payloads.
+pub const STEG_CHUNK_ID: &[u8; 4] = b"stEg";
+
+pub struct PngChunk<'a> {
+    pub id: [u8; 4],
+    pub data: &'a [u8],
+    /// Byte offset of the chunk's length field within the file.
+    pub offset: usize,
+}
+
+impl PngChunk<'_> {
+    /// Byte offset just past the chunk's CRC.
+    pub fn end(&self) -> usize {
+        self.offset + 12 + self.data.len()
+    }
+}
+
+pub fn is_png(bytes: &[u8]) -> bool {
+    bytes.starts_with(PNG_SIGNATURE)
+}
+
+/// Splits a PNG file into its chunks, stopping after IEND.
+pub fn parse_png_chunks(bytes: &[u8]) -> Result<Vec<PngChunk<'_>>, Error> {
+    if !is_png(bytes) {
+        return Err(Error::Format(
+            "Not a PNG file: missing signature".to_string(),
+        ));
+    }
+
+    let mut chunks = Vec::new();
+    let mut offset = PNG_SIGNATURE.len();
+    while offset + 12 <= bytes.len() {
+        let len = u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize;
+        let mut id = [0u8; 4];
+        id.copy_from_slice(&bytes[offset + 4..offset + 8]);
+        let data_start = offset + 8;
+        if data_start + len + 4 > bytes.len() {
+            return Err(Error::Format(format!(
+                "Corrupted PNG file: chunk '{}' extends past end of file",
+                String::from_utf8_lossy(&id)
+            )));
+        }
+        let chunk = PngChunk {
+            id,
+            data: &bytes[data_start..data_start + len],
+            offset,
+        };
+        offset = chunk.end();
+        chunks.push(chunk);
+        if &id == IEND_ID {
+            return Ok(chunks);
+        }
+    }
+    Err(Error::Format(
+        "Corrupted PNG file: no IEND chunk".to_string(),
+    ))
+}
+
+/// Byte offset just past the IEND chunk, i.e. the logical end of the image.
+pub fn png_end(bytes: &[u8]) -> Result<usize, Error> {
+    let chunks = parse_png_chunks(bytes)?;
+    Ok(chunks.last().map_or(PNG_SIGNATURE.len(), |c| c.end()))
+}
+
+/// Returns a copy of `bytes` with a new chunk inserted just before IEND.
+/// Any existing chunks with the same id are dropped.
+pub fn insert_png_chunk(bytes: &[u8], id: &[u8; 4], data: &[u8]) -> Result<Vec<u8>, Error> {
+    let chunks = parse_png_chunks(bytes)?;
+
+    let mut out = Vec::with_capacity(bytes.len() + data.len() + 12);
+    out.extend_from_slice(PNG_SIGNATURE);
+    for chunk in &chunks {
+        if &chunk.id == id {
+            continue;
+        }
+        if &chunk.id == IEND_ID {
+            write_png_chunk(&mut out, id, data);
+        }
+        out.extend_from_slice(&bytes[chunk.offset..chunk.end()]);
+    }
+    Ok(out)
+}
+
+pub fn find_png_chunk<'a>(bytes: &'a [u8], id: &[u8; 4]) -> Result<Option<&'a [u8]>, Error> {
+    Ok(parse_png_chunks(bytes)?
+        .into_iter()
+        .find(|c| &c.id == id)
+        .map(|c| c.data))
+}
+
+fn write_png_chunk(out: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(id);
+    hasher.update(data);
+
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(id);
+    out.extend_from_slice(data);
+    out.extend_from_slice(&hasher.finalize().to_be_bytes());
+}
@@ -0,0 +1,14 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+
+/// Deterministic random generator derived from a user-supplied key.
+/// `context` separates the streams used by different features, so the same
+/// key never yields correlated sequences across methods.
+pub fn keyed_rng(context: &str, key: &str) -> ChaCha20Rng {
+    let mut hasher = Sha256::new();
+    hasher.update(context.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(key.as_bytes());
+    ChaCha20Rng::from_seed(hasher.finalize().into())
+}
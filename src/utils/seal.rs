@@ -0,0 +1,259 @@
+use crate::error::Error;
+use crate::utils::keyfile::{
+    decode_key_hex, encode_hex, read_secret_key, write_secret_key, KEY_LEN,
+};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// Marks a payload sealed to one or more X25519 recipients.
+pub const SEAL_MAGIC: &[u8; 8] = b"stgseal1";
+
+const TAG_LEN: usize = 16;
+const WRAPPED_KEY_LEN: usize = KEY_LEN + TAG_LEN;
+const WRAP_INFO: &[u8] = b"steganography seal v1";
+const MAX_RECIPIENTS: usize = u8::MAX as usize;
+
+// Every key is used to encrypt exactly one message, so a fixed nonce is safe.
+const ZERO_NONCE: [u8; 12] = [0; 12];
+
+/// An X25519 secret key, stored on disk as hex with its public key in a
+/// leading comment line.
+pub struct Identity {
+    secret: StaticSecret,
+}
+
+impl Identity {
+    pub fn generate() -> Self {
+        Self {
+            secret: StaticSecret::random_from_rng(OsRng),
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        Ok(Self {
+            secret: StaticSecret::from(read_secret_key(path)?),
+        })
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from(&self.secret)
+    }
+
+    /// Writes the identity file produced by `keygen`.
+    pub fn write_to_file(&self, path: &str) -> Result<(), Error> {
+        write_secret_key(
+            path,
+            &[format!(
+                "public key: {}",
+                encode_hex(self.public_key().as_bytes())
+            )],
+            self.secret.as_bytes(),
+        )
+    }
+}
+
+/// Parses a recipient public key given as 64 hex digits.
+pub fn parse_recipient(recipient: &str) -> Result<PublicKey, Error> {
+    decode_key_hex(recipient.trim())
+        .map(PublicKey::from)
+        .ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "Invalid recipient '{}': expected a 64-digit hex public key",
+                recipient
+            ))
+        })
+}
+
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.starts_with(SEAL_MAGIC)
+}
+
+/// Encrypts `data` so that any one of `recipients` can open it.
+///
+/// Layout: magic, ephemeral public key, recipient count (1 byte), one
+/// wrapped file key per recipient, then the ChaCha20-Poly1305 ciphertext of
+/// `data` under the file key with everything before it as associated data.
+/// Wrapped keys carry no recipient identifiers; `open` tries each in turn.
+pub fn seal(data: &[u8], recipients: &[PublicKey]) -> Result<Vec<u8>, Error> {
+    if recipients.is_empty() {
+        return Err(Error::InvalidInput(
+            "At least one recipient is required to seal a payload".to_string(),
+        ));
+    }
+    if recipients.len() > MAX_RECIPIENTS {
+        return Err(Error::InvalidInput(format!(
+            "Too many recipients: {} (at most {})",
+            recipients.len(),
+            MAX_RECIPIENTS
+        )));
+    }
+
+    let mut file_key = [0u8; KEY_LEN];
+    OsRng.fill_bytes(&mut file_key);
+
+    // A fresh secret shared by all recipients; it is dropped once the file
+    // key has been wrapped.
+    let ephemeral = StaticSecret::random_from_rng(OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+
+    let mut sealed = Vec::with_capacity(
+        SEAL_MAGIC.len() + KEY_LEN + 1 + recipients.len() * WRAPPED_KEY_LEN + data.len() + TAG_LEN,
+    );
+    sealed.extend_from_slice(SEAL_MAGIC);
+    sealed.extend_from_slice(ephemeral_public.as_bytes());
+    sealed.push(recipients.len() as u8);
+
+    for recipient in recipients {
+        let shared = ephemeral.diffie_hellman(recipient);
+        if !shared.was_contributory() {
+            return Err(Error::InvalidInput(format!(
+                "Invalid recipient {}: not a usable X25519 public key",
+                encode_hex(recipient.as_bytes())
+            )));
+        }
+        let wrap_key = derive_wrap_key(shared.as_bytes(), &ephemeral_public, recipient);
+        let wrapped = ChaCha20Poly1305::new(Key::from_slice(&wrap_key))
+            .encrypt(Nonce::from_slice(&ZERO_NONCE), file_key.as_slice())
+            .map_err(|_| Error::Crypto("Failed to wrap the payload key".to_string()))?;
+        sealed.extend_from_slice(&wrapped);
+    }
+
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&file_key))
+        .encrypt(
+            Nonce::from_slice(&ZERO_NONCE),
+            Payload {
+                msg: data,
+                aad: &sealed,
+            },
+        )
+        .map_err(|_| Error::Crypto("Failed to encrypt the payload".to_string()))?;
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypts a payload produced by `seal` with the identity of one of its
+/// recipients.
+pub fn open(sealed: &[u8], identity: &Identity) -> Result<Vec<u8>, Error> {
+    let header_start = SEAL_MAGIC.len();
+    let count_offset = header_start + KEY_LEN;
+    if !is_sealed(sealed) || sealed.len() <= count_offset {
+        return Err(Error::Corrupt(
+            "Corrupted data: sealed payload header is truncated".to_string(),
+        ));
+    }
+
+    let mut ephemeral_bytes = [0u8; KEY_LEN];
+    ephemeral_bytes.copy_from_slice(&sealed[header_start..count_offset]);
+    let ephemeral_public = PublicKey::from(ephemeral_bytes);
+
+    let recipient_count = sealed[count_offset] as usize;
+    let ciphertext_offset = count_offset + 1 + recipient_count * WRAPPED_KEY_LEN;
+    if sealed.len() < ciphertext_offset + TAG_LEN {
+        return Err(Error::Corrupt(
+            "Corrupted data: sealed payload is truncated".to_string(),
+        ));
+    }
+
+    let shared = identity.secret.diffie_hellman(&ephemeral_public);
+    if !shared.was_contributory() {
+        return Err(Error::Corrupt(
+            "Corrupted data: sealed payload has an invalid ephemeral key".to_string(),
+        ));
+    }
+    let wrap_key = derive_wrap_key(shared.as_bytes(), &ephemeral_public, &identity.public_key());
+    let wrap_cipher = ChaCha20Poly1305::new(Key::from_slice(&wrap_key));
+
+    let file_key = sealed[count_offset + 1..ciphertext_offset]
+        .chunks_exact(WRAPPED_KEY_LEN)
+        .find_map(|wrapped| {
+            wrap_cipher
+                .decrypt(Nonce::from_slice(&ZERO_NONCE), wrapped)
+                .ok()
+        })
+        .ok_or_else(|| Error::Crypto("The payload is not sealed to this identity".to_string()))?;
+
+    ChaCha20Poly1305::new(Key::from_slice(&file_key))
+        .decrypt(
+            Nonce::from_slice(&ZERO_NONCE),
+            Payload {
+                msg: &sealed[ciphertext_offset..],
+                aad: &sealed[..ciphertext_offset],
+            },
+        )
+        .map_err(|_| {
+            Error::Corrupt("Corrupted data: sealed payload failed authentication".to_string())
+        })
+}
+
+fn derive_wrap_key(
+    shared: &[u8; KEY_LEN],
+    ephemeral_public: &PublicKey,
+    recipient: &PublicKey,
+) -> [u8; KEY_LEN] {
+    let mut salt = [0u8; KEY_LEN * 2];
+    salt[..KEY_LEN].copy_from_slice(ephemeral_public.as_bytes());
+    salt[KEY_LEN..].copy_from_slice(recipient.as_bytes());
+    let mut wrap_key = [0u8; KEY_LEN];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(WRAP_INFO, &mut wrap_key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    wrap_key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_recipient_can_open() {
+        let alice = Identity::generate();
+        let bob = Identity::generate();
+        let sealed = seal(b"hidden data", &[alice.public_key(), bob.public_key()]).unwrap();
+        assert!(is_sealed(&sealed));
+        assert_eq!(open(&sealed, &alice).unwrap(), b"hidden data");
+        assert_eq!(open(&sealed, &bob).unwrap(), b"hidden data");
+    }
+
+    #[test]
+    fn other_identities_cannot_open() {
+        let alice = Identity::generate();
+        let sealed = seal(b"hidden data", &[alice.public_key()]).unwrap();
+        assert!(matches!(
+            open(&sealed, &Identity::generate()),
+            Err(Error::Crypto(_))
+        ));
+    }
+
+    #[test]
+    fn detects_tampering() {
+        let alice = Identity::generate();
+        let sealed = seal(b"hidden data", &[alice.public_key()]).unwrap();
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(open(&tampered, &alice), Err(Error::Corrupt(_))));
+        assert!(matches!(
+            open(&sealed[..sealed.len() - 20], &alice),
+            Err(Error::Corrupt(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_recipients() {
+        assert!(matches!(seal(b"data", &[]), Err(Error::InvalidInput(_))));
+        // The identity element gives a non-contributory shared secret.
+        assert!(matches!(
+            seal(b"data", &[PublicKey::from([0u8; KEY_LEN])]),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(parse_recipient("not hex").is_err());
+        let key = Identity::generate().public_key();
+        let parsed = parse_recipient(&encode_hex(key.as_bytes())).unwrap();
+        assert_eq!(parsed.as_bytes(), key.as_bytes());
+    }
+}
@@ -0,0 +1,253 @@
+use crate::error::Error;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+/// Marks a payload holding one Shamir share of a larger payload.
+pub const SHARE_MAGIC: &[u8; 8] = b"stgshar1";
+
+const SET_ID_LEN: usize = 8;
+const HEADER_LEN: usize = SHARE_MAGIC.len() + SET_ID_LEN + 3;
+
+/// One share as stored in a carrier. Shares split together carry the same
+/// random set id; `index` is the x coordinate the share was evaluated at.
+pub struct Share {
+    pub set_id: [u8; SET_ID_LEN],
+    pub threshold: u8,
+    pub total: u8,
+    pub index: u8,
+    pub data: Vec<u8>,
+}
+
+/// Multiplication in GF(2^8) with the AES polynomial x^8 + x^4 + x^3 + x + 1.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1B;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// Multiplicative inverse of a non-zero element, as a^254.
+fn gf_inv(a: u8) -> u8 {
+    (0..7)
+        .fold((1, a), |(result, square), _| {
+            let square = gf_mul(square, square);
+            (gf_mul(result, square), square)
+        })
+        .0
+}
+
+/// Splits `secret` into `total` encoded shares, any `threshold` of which
+/// recover it while fewer reveal nothing about it.
+pub fn split(secret: &[u8], threshold: u8, total: u8) -> Result<Vec<Vec<u8>>, Error> {
+    if threshold < 2 || threshold > total {
+        return Err(Error::InvalidInput(format!(
+            "The threshold must be between 2 and the number of carriers ({}), got {}",
+            total, threshold
+        )));
+    }
+
+    let mut set_id = [0u8; SET_ID_LEN];
+    OsRng.fill_bytes(&mut set_id);
+    let mut shares: Vec<Vec<u8>> = (1..=total)
+        .map(|index| {
+            let mut share = Vec::with_capacity(HEADER_LEN + secret.len());
+            share.extend_from_slice(SHARE_MAGIC);
+            share.extend_from_slice(&set_id);
+            share.extend_from_slice(&[threshold, total, index]);
+            share
+        })
+        .collect();
+
+    // One random polynomial of degree threshold - 1 per secret byte, with the
+    // byte as its constant term.
+    let mut coefficients = vec![0u8; threshold as usize - 1];
+    for &byte in secret {
+        OsRng.fill_bytes(&mut coefficients);
+        for (share, x) in shares.iter_mut().zip(1..=total) {
+            let y = coefficients
+                .iter()
+                .rev()
+                .fold(0, |acc, &coefficient| gf_mul(acc, x) ^ coefficient);
+            share.push(gf_mul(y, x) ^ byte);
+        }
+    }
+    Ok(shares)
+}
+
+pub fn is_share(data: &[u8]) -> bool {
+    data.starts_with(SHARE_MAGIC)
+}
+
+pub fn parse_share(data: &[u8]) -> Result<Share, Error> {
+    if !is_share(data) || data.len() < HEADER_LEN {
+        return Err(Error::Corrupt(
+            "Corrupted data: share header is truncated".to_string(),
+        ));
+    }
+    let mut set_id = [0u8; SET_ID_LEN];
+    set_id.copy_from_slice(&data[SHARE_MAGIC.len()..SHARE_MAGIC.len() + SET_ID_LEN]);
+    let [threshold, total, index] = [
+        data[HEADER_LEN - 3],
+        data[HEADER_LEN - 2],
+        data[HEADER_LEN - 1],
+    ];
+    if threshold < 2 || threshold > total || index == 0 || index > total {
+        return Err(Error::Corrupt(
+            "Corrupted data: invalid share header".to_string(),
+        ));
+    }
+    Ok(Share {
+        set_id,
+        threshold,
+        total,
+        index,
+        data: data[HEADER_LEN..].to_vec(),
+    })
+}
+
+/// Recovers the secret from shares of one set by Lagrange interpolation at
+/// x = 0. Duplicate shares are ignored.
+pub fn combine(shares: &[Share]) -> Result<Vec<u8>, Error> {
+    let first = shares
+        .first()
+        .ok_or_else(|| Error::NotFound("No shares to combine".to_string()))?;
+    if shares.iter().any(|share| {
+        share.set_id != first.set_id
+            || share.threshold != first.threshold
+            || share.total != first.total
+    }) {
+        return Err(Error::Corrupt(
+            "The stego files hold shares of different payloads".to_string(),
+        ));
+    }
+
+    let mut distinct: Vec<&Share> = Vec::new();
+    for share in shares {
+        if !distinct.iter().any(|seen| seen.index == share.index) {
+            distinct.push(share);
+        }
+    }
+    if distinct.len() < first.threshold as usize {
+        return Err(Error::NotFound(format!(
+            "Only {} of the {} shares needed to reconstruct the payload were found",
+            distinct.len(),
+            first.threshold
+        )));
+    }
+    let distinct = &distinct[..first.threshold as usize];
+    if distinct
+        .iter()
+        .any(|share| share.data.len() != first.data.len())
+    {
+        return Err(Error::Corrupt(
+            "Corrupted data: shares differ in length".to_string(),
+        ));
+    }
+
+    let weights: Vec<u8> = distinct
+        .iter()
+        .map(|share| {
+            distinct
+                .iter()
+                .filter(|other| other.index != share.index)
+                .fold(1, |weight, other| {
+                    gf_mul(
+                        weight,
+                        gf_mul(other.index, gf_inv(other.index ^ share.index)),
+                    )
+                })
+        })
+        .collect();
+
+    Ok((0..first.data.len())
+        .map(|i| {
+            distinct
+                .iter()
+                .zip(&weights)
+                .fold(0, |acc, (share, &weight)| {
+                    acc ^ gf_mul(share.data[i], weight)
+                })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(shares: &[Vec<u8>]) -> Vec<Share> {
+        shares
+            .iter()
+            .map(|share| parse_share(share).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn gf_inverse() {
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn any_threshold_shares_recover_the_secret() {
+        let secret: Vec<u8> = (0..=255).collect();
+        let shares = split(&secret, 3, 5).unwrap();
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let subset = [shares[c].clone(), shares[a].clone(), shares[b].clone()];
+                    assert_eq!(combine(&parsed(&subset)).unwrap(), secret);
+                }
+            }
+        }
+        assert_eq!(combine(&parsed(&shares)).unwrap(), secret);
+    }
+
+    #[test]
+    fn fewer_than_threshold_shares_fail() {
+        let shares = split(b"secret", 3, 5).unwrap();
+        let two = parsed(&[shares[0].clone(), shares[4].clone(), shares[0].clone()]);
+        assert!(matches!(combine(&two), Err(Error::NotFound(_))));
+        assert!(matches!(combine(&[]), Err(Error::NotFound(_))));
+    }
+
+    #[test]
+    fn rejects_mixed_sets() {
+        let first = split(b"secret", 2, 3).unwrap();
+        let second = split(b"secret", 2, 3).unwrap();
+        let mixed = parsed(&[first[0].clone(), second[1].clone()]);
+        assert!(matches!(combine(&mixed), Err(Error::Corrupt(_))));
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        let share = split(b"secret", 2, 3).unwrap().remove(0);
+        for (offset, value) in [(0, 1), (1, 1), (2, 0), (2, 4)] {
+            let mut invalid = share.clone();
+            invalid[HEADER_LEN - 3 + offset] = value;
+            assert!(matches!(parse_share(&invalid), Err(Error::Corrupt(_))));
+        }
+        assert!(matches!(
+            parse_share(&share[..HEADER_LEN - 1]),
+            Err(Error::Corrupt(_))
+        ));
+        assert!(matches!(
+            split(b"secret", 1, 3),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            split(b"secret", 4, 3),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
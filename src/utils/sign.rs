@@ -0,0 +1,245 @@
+use crate::error::Error;
+use crate::traits::ContentType;
+use crate::utils::keyfile::{
+    decode_key_hex, encode_hex, read_secret_key, write_secret_key, KEY_LEN,
+};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey, SIGNATURE_LENGTH};
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+
+/// Marks a payload carrying an embedded Ed25519 signature.
+pub const SIGN_MAGIC: &[u8; 8] = b"stgsign1";
+
+const SIGNATURE_CONTEXT: &[u8] = b"steganography signed payload v1";
+const FINGERPRINT_LEN: usize = 16;
+const HEADER_LEN: usize = SIGN_MAGIC.len() + KEY_LEN + SIGNATURE_LENGTH;
+
+/// An Ed25519 signing key, stored on disk in the same format as identities.
+pub struct SignerKey {
+    key: SigningKey,
+}
+
+impl SignerKey {
+    pub fn generate() -> Self {
+        Self {
+            key: SigningKey::generate(&mut OsRng),
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        Ok(Self {
+            key: SigningKey::from_bytes(&read_secret_key(path)?),
+        })
+    }
+
+    pub fn fingerprint(&self) -> String {
+        fingerprint(self.key.verifying_key().as_bytes())
+    }
+
+    /// Writes the signing key file produced by `keygen --signing`.
+    pub fn write_to_file(&self, path: &str) -> Result<(), Error> {
+        write_secret_key(
+            path,
+            &[
+                format!("signer fingerprint: {}", self.fingerprint()),
+                format!(
+                    "public key: {}",
+                    encode_hex(self.key.verifying_key().as_bytes())
+                ),
+            ],
+            self.key.as_bytes(),
+        )
+    }
+}
+
+/// Short identifier for a signer: the first 16 bytes of the SHA-256 of its
+/// public key, in hex.
+pub fn fingerprint(public_key: &[u8; KEY_LEN]) -> String {
+    encode_hex(&Sha256::digest(public_key)[..FINGERPRINT_LEN])
+}
+
+/// Accepts either a signer fingerprint or a full public key and returns the
+/// fingerprint.
+pub fn parse_signer(signer: &str) -> Result<String, Error> {
+    let signer = signer.trim().to_lowercase();
+    if let Some(public_key) = decode_key_hex(&signer) {
+        return Ok(fingerprint(&public_key));
+    }
+    if signer.len() == FINGERPRINT_LEN * 2 && signer.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(signer);
+    }
+    Err(Error::InvalidInput(format!(
+        "Invalid signer '{}': expected a fingerprint or a 64-digit hex public key",
+        signer
+    )))
+}
+
+pub fn is_signed(data: &[u8]) -> bool {
+    data.starts_with(SIGN_MAGIC)
+}
+
+/// Prefixes `data` with a header holding the signer's public key and a
+/// signature over the content type and data.
+///
+/// Layout: magic, public key (32 bytes), signature (64 bytes), data.
+pub fn sign(data: &[u8], content_type: ContentType, signer: &SignerKey) -> Vec<u8> {
+    let mut signed = sign_detached(data, content_type, signer);
+    signed.extend_from_slice(data);
+    signed
+}
+
+/// The header [`sign`] would prefix `data` with, to be kept apart from the
+/// data as a detached signature.
+pub fn sign_detached(data: &[u8], content_type: ContentType, signer: &SignerKey) -> Vec<u8> {
+    let signature = signer.key.sign(&signed_message(data, content_type));
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(SIGN_MAGIC);
+    header.extend_from_slice(signer.key.verifying_key().as_bytes());
+    header.extend_from_slice(&signature.to_bytes());
+    header
+}
+
+/// The outcome of checking a payload produced by `sign`.
+pub struct SignedPayload {
+    pub fingerprint: String,
+    pub valid: bool,
+    pub data: Vec<u8>,
+}
+
+pub fn verify(signed: &[u8], content_type: ContentType) -> Result<SignedPayload, Error> {
+    if !is_signed(signed) || signed.len() < HEADER_LEN {
+        return Err(Error::Corrupt(
+            "Corrupted data: signed payload header is truncated".to_string(),
+        ));
+    }
+
+    let mut public_key = [0u8; KEY_LEN];
+    public_key.copy_from_slice(&signed[SIGN_MAGIC.len()..SIGN_MAGIC.len() + KEY_LEN]);
+    let signature = Signature::from_slice(&signed[SIGN_MAGIC.len() + KEY_LEN..HEADER_LEN])
+        .map_err(|e| Error::Corrupt(format!("Corrupted data: malformed signature: {}", e)))?;
+    let data = &signed[HEADER_LEN..];
+
+    let valid = VerifyingKey::from_bytes(&public_key)
+        .and_then(|key| key.verify_strict(&signed_message(data, content_type), &signature))
+        .is_ok();
+
+    Ok(SignedPayload {
+        fingerprint: fingerprint(&public_key),
+        valid,
+        data: data.to_vec(),
+    })
+}
+
+/// Checks a signature written by [`sign_detached`] against `data`.
+pub fn verify_detached(
+    signature: &[u8],
+    data: &[u8],
+    content_type: ContentType,
+) -> Result<SignedPayload, Error> {
+    if !is_signed(signature) || signature.len() != HEADER_LEN {
+        return Err(Error::Corrupt(format!(
+            "Corrupted detached signature: expected {} bytes starting with the signature magic",
+            HEADER_LEN
+        )));
+    }
+    verify(&[signature, data].concat(), content_type)
+}
+
+fn signed_message(data: &[u8], content_type: ContentType) -> Vec<u8> {
+    let mut message = Vec::with_capacity(SIGNATURE_CONTEXT.len() + 1 + data.len());
+    message.extend_from_slice(SIGNATURE_CONTEXT);
+    message.push(content_type.to_u8());
+    message.extend_from_slice(data);
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_untouched_data() {
+        let signer = SignerKey::generate();
+        let signed = sign(b"hidden data", ContentType::Image, &signer);
+        assert!(is_signed(&signed));
+
+        let payload = verify(&signed, ContentType::Image).unwrap();
+        assert!(payload.valid);
+        assert_eq!(payload.fingerprint, signer.fingerprint());
+        assert_eq!(payload.data, b"hidden data");
+    }
+
+    #[test]
+    fn rejects_tampered_data_and_content_type() {
+        let signer = SignerKey::generate();
+        let signed = sign(b"hidden data", ContentType::Image, &signer);
+
+        let mut tampered = signed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(!verify(&tampered, ContentType::Image).unwrap().valid);
+        assert!(!verify(&signed, ContentType::Audio).unwrap().valid);
+    }
+
+    #[test]
+    fn rejects_a_swapped_public_key() {
+        let signed = sign(b"hidden data", ContentType::Image, &SignerKey::generate());
+        let other = SignerKey::generate();
+
+        let mut forged = signed.clone();
+        forged[SIGN_MAGIC.len()..SIGN_MAGIC.len() + KEY_LEN]
+            .copy_from_slice(other.key.verifying_key().as_bytes());
+        let payload = verify(&forged, ContentType::Image).unwrap();
+        assert!(!payload.valid);
+        assert_eq!(payload.fingerprint, other.fingerprint());
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        let signed = sign(b"", ContentType::Image, &SignerKey::generate());
+        assert!(matches!(
+            verify(&signed[..HEADER_LEN - 1], ContentType::Image),
+            Err(Error::Corrupt(_))
+        ));
+    }
+
+    #[test]
+    fn verifies_detached_signatures() {
+        let signer = SignerKey::generate();
+        let signature = sign_detached(b"hidden data", ContentType::Image, &signer);
+        assert_eq!(signature.len(), HEADER_LEN);
+
+        let payload = verify_detached(&signature, b"hidden data", ContentType::Image).unwrap();
+        assert!(payload.valid);
+        assert_eq!(payload.fingerprint, signer.fingerprint());
+        assert!(
+            !verify_detached(&signature, b"hidden datA", ContentType::Image)
+                .unwrap()
+                .valid
+        );
+        assert!(matches!(
+            verify_detached(&signature[1..], b"hidden data", ContentType::Image),
+            Err(Error::Corrupt(_))
+        ));
+        assert!(matches!(
+            verify_detached(
+                &sign(b"x", ContentType::Image, &signer),
+                b"x",
+                ContentType::Image
+            ),
+            Err(Error::Corrupt(_))
+        ));
+    }
+
+    #[test]
+    fn parses_fingerprints_and_public_keys() {
+        let signer = SignerKey::generate();
+        let public_key = encode_hex(signer.key.verifying_key().as_bytes());
+        assert_eq!(parse_signer(&public_key).unwrap(), signer.fingerprint());
+        assert_eq!(
+            parse_signer(&signer.fingerprint().to_uppercase()).unwrap(),
+            signer.fingerprint()
+        );
+        assert!(parse_signer("abc").is_err());
+    }
+}
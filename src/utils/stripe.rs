@@ -0,0 +1,199 @@
+use crate::error::Error;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+/// Marks a payload holding one piece of a payload striped across carriers.
+pub const STRIPE_MAGIC: &[u8; 8] = b"stgstrp1";
+
+const SET_ID_LEN: usize = 8;
+pub const STRIPE_HEADER_LEN: usize = STRIPE_MAGIC.len() + SET_ID_LEN + 2 + 2;
+
+/// One piece as stored in a carrier. Pieces striped together carry the same
+/// random set id; `index` counts from 0 in carrier order.
+pub struct Stripe {
+    pub set_id: [u8; SET_ID_LEN],
+    pub index: u16,
+    pub total: u16,
+    pub data: Vec<u8>,
+}
+
+/// Splits `len` bytes into one piece per carrier, proportionally to each
+/// carrier's capacity in bytes. Fails when the carriers cannot hold `len`
+/// bytes between them.
+pub fn stripe_sizes(len: usize, capacities: &[usize]) -> Result<Vec<usize>, Error> {
+    let available: usize = capacities.iter().sum();
+    if len > available {
+        return Err(Error::Capacity(format!(
+            "Carriers not large enough: {} bytes to hide, {} bytes available across {} carriers",
+            len,
+            available,
+            capacities.len()
+        )));
+    }
+
+    let mut sizes: Vec<usize> = capacities
+        .iter()
+        .map(|&capacity| (len as u128 * capacity as u128 / available as u128) as usize)
+        .collect();
+    // Hand the bytes lost to rounding to carriers with room to spare.
+    let mut remaining = len - sizes.iter().sum::<usize>();
+    for (size, &capacity) in sizes.iter_mut().zip(capacities) {
+        let extra = remaining.min(capacity - *size);
+        *size += extra;
+        remaining -= extra;
+    }
+    Ok(sizes)
+}
+
+/// Cuts `data` into pieces of the given sizes, each prefixed with the
+/// stripe header: magic, set id, index and total (u16, big-endian).
+pub fn stripe(data: &[u8], sizes: &[usize]) -> Result<Vec<Vec<u8>>, Error> {
+    let total = u16::try_from(sizes.len()).map_err(|_| {
+        Error::InvalidInput(format!("At most {} carriers can be striped", u16::MAX))
+    })?;
+    let mut set_id = [0u8; SET_ID_LEN];
+    OsRng.fill_bytes(&mut set_id);
+
+    let mut offset = 0;
+    Ok(sizes
+        .iter()
+        .zip(0..total)
+        .map(|(&size, index)| {
+            let mut piece = Vec::with_capacity(STRIPE_HEADER_LEN + size);
+            piece.extend_from_slice(STRIPE_MAGIC);
+            piece.extend_from_slice(&set_id);
+            piece.extend_from_slice(&index.to_be_bytes());
+            piece.extend_from_slice(&total.to_be_bytes());
+            piece.extend_from_slice(&data[offset..offset + size]);
+            offset += size;
+            piece
+        })
+        .collect())
+}
+
+pub fn is_stripe(data: &[u8]) -> bool {
+    data.starts_with(STRIPE_MAGIC)
+}
+
+pub fn parse_stripe(data: &[u8]) -> Result<Stripe, Error> {
+    if !is_stripe(data) || data.len() < STRIPE_HEADER_LEN {
+        return Err(Error::Corrupt(
+            "Corrupted data: stripe header is truncated".to_string(),
+        ));
+    }
+    let mut set_id = [0u8; SET_ID_LEN];
+    set_id.copy_from_slice(&data[STRIPE_MAGIC.len()..STRIPE_MAGIC.len() + SET_ID_LEN]);
+    let counters = &data[STRIPE_MAGIC.len() + SET_ID_LEN..STRIPE_HEADER_LEN];
+    let index = u16::from_be_bytes([counters[0], counters[1]]);
+    let total = u16::from_be_bytes([counters[2], counters[3]]);
+    if index >= total {
+        return Err(Error::Corrupt(
+            "Corrupted data: invalid stripe header".to_string(),
+        ));
+    }
+    Ok(Stripe {
+        set_id,
+        index,
+        total,
+        data: data[STRIPE_HEADER_LEN..].to_vec(),
+    })
+}
+
+/// Joins the pieces of one striped payload, given in any order. Reports
+/// every missing piece by its 1-based position.
+pub fn reassemble(mut stripes: Vec<Stripe>) -> Result<Vec<u8>, Error> {
+    let (set_id, total) = match stripes.first() {
+        Some(first) => (first.set_id, first.total),
+        None => return Err(Error::NotFound("No pieces to reassemble".to_string())),
+    };
+    if stripes
+        .iter()
+        .any(|stripe| stripe.set_id != set_id || stripe.total != total)
+    {
+        return Err(Error::Corrupt(
+            "The stego files hold pieces of different payloads".to_string(),
+        ));
+    }
+
+    stripes.sort_by_key(|stripe| stripe.index);
+    stripes.dedup_by_key(|stripe| stripe.index);
+    let missing: Vec<String> = (0..total)
+        .filter(|index| !stripes.iter().any(|stripe| stripe.index == *index))
+        .map(|index| (index as u32 + 1).to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(Error::NotFound(format!(
+            "Missing piece(s) {} of {}",
+            missing.join(", "),
+            total
+        )));
+    }
+
+    Ok(stripes.into_iter().flat_map(|stripe| stripe.data).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(pieces: &[Vec<u8>]) -> Vec<Stripe> {
+        pieces
+            .iter()
+            .map(|piece| parse_stripe(piece).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn sizes_follow_capacity_and_add_up() {
+        assert_eq!(stripe_sizes(100, &[100, 100]).unwrap(), [50, 50]);
+        assert_eq!(stripe_sizes(10, &[1, 2, 7]).unwrap(), [1, 2, 7]);
+        let sizes = stripe_sizes(1000, &[333, 333, 334, 1]).unwrap();
+        assert_eq!(sizes.iter().sum::<usize>(), 1000);
+        assert!(sizes.iter().zip([333, 333, 334, 1]).all(|(&s, c)| s <= c));
+        assert!(matches!(stripe_sizes(11, &[5, 5]), Err(Error::Capacity(_))));
+    }
+
+    #[test]
+    fn reassembles_pieces_in_any_order() {
+        let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let sizes = stripe_sizes(data.len(), &[300, 900, 50]).unwrap();
+        let pieces = stripe(&data, &sizes).unwrap();
+        assert!(pieces.iter().all(|piece| is_stripe(piece)));
+
+        let shuffled = [pieces[2].clone(), pieces[0].clone(), pieces[1].clone()];
+        assert_eq!(reassemble(parsed(&shuffled)).unwrap(), data);
+        let duplicated = [
+            pieces[1].clone(),
+            pieces[0].clone(),
+            pieces[1].clone(),
+            pieces[2].clone(),
+        ];
+        assert_eq!(reassemble(parsed(&duplicated)).unwrap(), data);
+    }
+
+    #[test]
+    fn reports_missing_pieces() {
+        let pieces = stripe(&[7; 40], &[10, 10, 10, 10]).unwrap();
+        let Err(Error::NotFound(message)) = reassemble(parsed(&[pieces[1].clone()])) else {
+            panic!("missing pieces were not reported");
+        };
+        assert!(message.contains("1, 3, 4 of 4"), "{}", message);
+        assert!(matches!(reassemble(Vec::new()), Err(Error::NotFound(_))));
+    }
+
+    #[test]
+    fn rejects_mixed_sets_and_invalid_headers() {
+        let first = stripe(&[1; 20], &[10, 10]).unwrap();
+        let second = stripe(&[2; 20], &[10, 10]).unwrap();
+        let mixed = [first[0].clone(), second[1].clone()];
+        assert!(matches!(reassemble(parsed(&mixed)), Err(Error::Corrupt(_))));
+
+        let mut invalid = first[0].clone();
+        invalid[STRIPE_HEADER_LEN - 3] = 2;
+        assert!(matches!(parse_stripe(&invalid), Err(Error::Corrupt(_))));
+        assert!(matches!(
+            parse_stripe(&first[0][..STRIPE_HEADER_LEN - 1]),
+            Err(Error::Corrupt(_))
+        ));
+    }
+}
@@ -0,0 +1,350 @@
+// RIFF/WAVE reader and writer for 16-bit integer PCM.
+//
+// Only the `fmt ` and `data` chunks are interpreted. Every other chunk
+// (LIST/INFO, bext, cue, smpl, ...) is kept as raw bytes and written back in
+// its original position, and the `fmt ` chunk itself is copied verbatim so
+// WAVE_FORMAT_EXTENSIBLE channel masks survive.
+
+use crate::error::Error;
+
+const RIFF_ID: &[u8; 4] = b"RIFF";
+const WAVE_ID: &[u8; 4] = b"WAVE";
+const FMT_ID: &[u8; 4] = b"fmt ";
+const DATA_ID: &[u8; 4] = b"data";
+
+/// Custom chunk used to carry hidden payloads.
+pub const STEG_CHUNK_ID: &[u8; 4] = b"steg";
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+struct Chunk {
+    id: [u8; 4],
+    data: Vec<u8>,
+}
+
+pub struct WavFile {
+    pub channels: u16,
+    pub sample_rate: u32,
+    chunks: Vec<Chunk>,
+}
+
+impl WavFile {
+    /// Creates an empty WAV description for 16-bit PCM with the given layout.
+    pub fn new(channels: u16, sample_rate: u32) -> Result<Self, Error> {
+        let block_align = channels.checked_mul(2);
+        let byte_rate = block_align.and_then(|align| sample_rate.checked_mul(align as u32));
+        let (Some(block_align), Some(byte_rate)) = (block_align, byte_rate) else {
+            return Err(Error::Format(format!(
+                "Cannot write a WAV header for {} channels at {} Hz",
+                channels, sample_rate
+            )));
+        };
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&WAVE_FORMAT_PCM.to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&sample_rate.to_le_bytes());
+        fmt.extend_from_slice(&byte_rate.to_le_bytes());
+        fmt.extend_from_slice(&block_align.to_le_bytes());
+        fmt.extend_from_slice(&16u16.to_le_bytes());
+
+        Ok(Self {
+            channels,
+            sample_rate,
+            chunks: vec![
+                Chunk {
+                    id: *FMT_ID,
+                    data: fmt,
+                },
+                Chunk {
+                    id: *DATA_ID,
+                    data: Vec::new(),
+                },
+            ],
+        })
+    }
+
+    /// Serializes the file with `samples` (interleaved) as the `data` chunk.
+    /// All other chunks are written back unchanged.
+    pub fn to_bytes(&self, samples: &[i16]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(WAVE_ID);
+
+        for chunk in &self.chunks {
+            if &chunk.id == DATA_ID {
+                let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+                write_chunk(&mut body, &chunk.id, &data);
+            } else {
+                write_chunk(&mut body, &chunk.id, &chunk.data);
+            }
+        }
+
+        let mut out = Vec::with_capacity(body.len() + 8);
+        out.extend_from_slice(RIFF_ID);
+        out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        out.extend_from_slice(&body);
+        out
+    }
+}
+
+/// Parses a RIFF/WAVE file, returning its description and the interleaved
+/// 16-bit samples.
+pub fn read_wav(bytes: &[u8]) -> Result<(WavFile, Vec<i16>), Error> {
+    let chunks = parse_riff_chunks(bytes)?;
+
+    let fmt = chunks
+        .iter()
+        .find(|c| &c.id == FMT_ID)
+        .ok_or_else(|| Error::Format("WAV file has no 'fmt ' chunk".to_string()))?;
+    if fmt.data.len() < 16 {
+        return Err(Error::Format(
+            "Corrupted WAV file: 'fmt ' chunk too short".to_string(),
+        ));
+    }
+    let le_u16 = |at: usize| u16::from_le_bytes([fmt.data[at], fmt.data[at + 1]]);
+    let mut format_tag = le_u16(0);
+    let channels = le_u16(2);
+    let sample_rate = u32::from_le_bytes(fmt.data[4..8].try_into().unwrap());
+    let bits_per_sample = le_u16(14);
+
+    if format_tag == WAVE_FORMAT_EXTENSIBLE {
+        // The sub-format GUID starts with the actual format tag.
+        if fmt.data.len() < 26 {
+            return Err(Error::Format(
+                "Corrupted WAV file: extensible 'fmt ' chunk too short".to_string(),
+            ));
+        }
+        format_tag = le_u16(24);
+    }
+
+    if format_tag != WAVE_FORMAT_PCM || bits_per_sample != 16 {
+        return Err(Error::Format(format!(
+            "WAV carrier must contain 16-bit integer PCM samples (format tag {:#06x}, {}-bit found)",
+            format_tag, bits_per_sample
+        )));
+    }
+
+    let data = chunks
+        .iter()
+        .find(|c| &c.id == DATA_ID)
+        .ok_or_else(|| Error::Format("WAV file has no 'data' chunk".to_string()))?;
+    let samples = data
+        .data
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect();
+
+    Ok((
+        WavFile {
+            channels,
+            sample_rate,
+            chunks,
+        },
+        samples,
+    ))
+}
+
+fn write_chunk(out: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+/// Returns a copy of `bytes` with a top-level chunk `id` appended to the RIFF
+/// body. Any existing chunks with the same id are dropped; everything else,
+/// including the sample data, is copied byte for byte.
+pub fn insert_wav_chunk(bytes: &[u8], id: &[u8; 4], data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut chunks = parse_riff_chunks(bytes)?;
+    chunks.retain(|c| &c.id != id);
+    chunks.push(Chunk {
+        id: *id,
+        data: data.to_vec(),
+    });
+
+    let mut body = Vec::new();
+    body.extend_from_slice(WAVE_ID);
+    for chunk in &chunks {
+        write_chunk(&mut body, &chunk.id, &chunk.data);
+    }
+
+    let mut out = Vec::with_capacity(body.len() + 8);
+    out.extend_from_slice(RIFF_ID);
+    out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+pub fn find_wav_chunk(bytes: &[u8], id: &[u8; 4]) -> Result<Option<Vec<u8>>, Error> {
+    Ok(parse_riff_chunks(bytes)?
+        .into_iter()
+        .find(|c| &c.id == id)
+        .map(|c| c.data))
+}
+
+/// Byte offset just past the RIFF chunk, i.e. the logical end of the file.
+/// Streamed files whose RIFF size is 0xFFFFFFFF or too large end at the end
+/// of the bytes, as [`read_wav`] reads them.
+pub fn wav_end(bytes: &[u8]) -> Result<usize, Error> {
+    if bytes.len() < 12 || &bytes[0..4] != RIFF_ID || &bytes[8..12] != WAVE_ID {
+        return Err(Error::Format(
+            "Not a WAV file: missing RIFF/WAVE header".to_string(),
+        ));
+    }
+    let riff_len = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+    Ok((8 + riff_len + (riff_len & 1)).min(bytes.len()))
+}
+
+/// Sets the RIFF size, and the data chunk size if it runs past the end, of
+/// a streamed WAV file to its actual length, so that bytes appended after it
+/// are not read as part of the file. Files with valid sizes are unchanged.
+pub fn fix_streamed_sizes(bytes: &mut [u8]) {
+    let riff_len = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+    if 8 + riff_len <= bytes.len() {
+        return;
+    }
+
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let len = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let start = offset + 8;
+        if start + len > bytes.len() {
+            if &bytes[offset..offset + 4] == DATA_ID {
+                let len = (bytes.len() - start) as u32;
+                bytes[offset + 4..offset + 8].copy_from_slice(&len.to_le_bytes());
+            }
+            break;
+        }
+        offset = start + len + (len & 1);
+    }
+    let riff_len = (bytes.len() - 8) as u32;
+    bytes[4..8].copy_from_slice(&riff_len.to_le_bytes());
+}
+
+fn parse_riff_chunks(bytes: &[u8]) -> Result<Vec<Chunk>, Error> {
+    if bytes.len() < 12 || &bytes[0..4] != RIFF_ID || &bytes[8..12] != WAVE_ID {
+        return Err(Error::Format(
+            "Not a WAV file: missing RIFF/WAVE header".to_string(),
+        ));
+    }
+
+    let riff_len = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+    let riff_end = (8 + riff_len).min(bytes.len());
+
+    let mut chunks = Vec::new();
+    let mut offset = 12;
+    while offset + 8 <= riff_end {
+        let mut id = [0u8; 4];
+        id.copy_from_slice(&bytes[offset..offset + 4]);
+        let len = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let start = offset + 8;
+        let mut end = start + len;
+        // Streaming encoders write the data chunk before they know its
+        // length and leave the size at 0xFFFFFFFF or too large; read what
+        // is there.
+        if end > bytes.len() && &id == DATA_ID {
+            end = bytes.len();
+        }
+        if end > bytes.len() {
+            return Err(Error::Format(format!(
+                "Corrupted WAV file: chunk '{}' extends past end of file",
+                String::from_utf8_lossy(&id)
+            )));
+        }
+        chunks.push(Chunk {
+            id,
+            data: bytes[start..end].to_vec(),
+        });
+        offset = end + (len & 1);
+    }
+    Ok(chunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples() -> Vec<i16> {
+        (0..2000)
+            .map(|i| ((i * 37) % 65536 - 32768) as i16)
+            .collect()
+    }
+
+    #[test]
+    fn wav_round_trip() {
+        let bytes = WavFile::new(2, 44100).unwrap().to_bytes(&samples());
+        let (file, read) = read_wav(&bytes).unwrap();
+        assert_eq!(file.channels, 2);
+        assert_eq!(file.sample_rate, 44100);
+        assert_eq!(read, samples());
+        assert_eq!(wav_end(&bytes).unwrap(), bytes.len());
+    }
+
+    #[test]
+    fn keeps_metadata_chunks() {
+        let bytes = WavFile::new(1, 8000).unwrap().to_bytes(&samples());
+        let bytes = insert_wav_chunk(&bytes, b"LIST", b"INFOtitle").unwrap();
+
+        let (file, read) = read_wav(&bytes).unwrap();
+        let rewritten = file.to_bytes(&read);
+        assert_eq!(rewritten, bytes);
+        assert_eq!(
+            find_wav_chunk(&rewritten, b"LIST").unwrap().as_deref(),
+            Some(&b"INFOtitle"[..])
+        );
+    }
+
+    #[test]
+    fn reads_streamed_data_size() {
+        let mut bytes = WavFile::new(1, 8000).unwrap().to_bytes(&samples());
+        // RIFF header (12) and fmt chunk (8 + 16), then the data chunk size.
+        bytes[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+        let (_, read) = read_wav(&bytes).unwrap();
+        assert_eq!(read, samples());
+    }
+
+    #[test]
+    fn streamed_file_ends_at_the_last_byte() {
+        let original = WavFile::new(1, 8000).unwrap().to_bytes(&samples());
+        let mut bytes = original.clone();
+        bytes[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        bytes[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(wav_end(&bytes).unwrap(), bytes.len());
+
+        fix_streamed_sizes(&mut bytes);
+        assert_eq!(bytes, original);
+        fix_streamed_sizes(&mut bytes);
+        assert_eq!(bytes, original);
+    }
+
+    #[test]
+    fn reads_extensible_format() {
+        let mut bytes = WavFile::new(2, 44100).unwrap().to_bytes(&samples());
+        let mut fmt = bytes[20..36].to_vec();
+        fmt[0..2].copy_from_slice(&WAVE_FORMAT_EXTENSIBLE.to_le_bytes());
+        fmt.extend_from_slice(&22u16.to_le_bytes());
+        fmt.extend_from_slice(&16u16.to_le_bytes());
+        fmt.extend_from_slice(&3u32.to_le_bytes());
+        fmt.extend_from_slice(&WAVE_FORMAT_PCM.to_le_bytes());
+        fmt.extend_from_slice(&[0; 14]);
+        bytes = insert_wav_chunk(&bytes, FMT_ID, &fmt).unwrap();
+
+        let (file, read) = read_wav(&bytes).unwrap();
+        assert_eq!(read, samples());
+        assert_eq!(
+            find_wav_chunk(&file.to_bytes(&read), FMT_ID).unwrap(),
+            Some(fmt)
+        );
+    }
+
+    #[test]
+    fn rejects_header_overflow() {
+        assert!(matches!(
+            WavFile::new(u16::MAX, 44100),
+            Err(Error::Format(_))
+        ));
+        assert!(matches!(WavFile::new(2, u32::MAX), Err(Error::Format(_))));
+    }
+}